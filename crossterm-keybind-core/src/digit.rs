@@ -0,0 +1,136 @@
+use crate::{KeyBindings, KeySequence, ParseKeyBindingError};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// The placeholder of a digit in the key bindings of an event with a digit, e.g. `"Alt+<digit>"`
+pub const DIGIT_PLACEHOLDER: &str = "<digit>";
+
+/// Key bindings with the `<digit>` placeholder, e.g. `["Alt+<digit>"]`, which bind an event with a
+/// digit, e.g. `SwitchTab(u8)`, to the keys of each digit from 0 to 9.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct DigitKeyBindings {
+    templates: Vec<String>,
+    digits: [KeyBindings; 10],
+}
+
+/// Replace the placeholders with the digit, the spans of errors are moved back to the template
+fn expand(template: &str, digit: u8) -> Result<KeySequence, ParseKeyBindingError> {
+    let placeholders: Vec<usize> = template.match_indices(DIGIT_PLACEHOLDER).map(|(i, _)| i).collect();
+    if placeholders.is_empty() {
+        return Err(ParseKeyBindingError::MissingPlaceholder {
+            span: 0..template.len(),
+        });
+    }
+    let s = template.replace(DIGIT_PLACEHOLDER, &digit.to_string());
+    KeySequence::parse(&s).map_err(|e| {
+        let start = e.span().start;
+        // each placeholder before the span is one char long in the expanded string
+        let shift = placeholders
+            .iter()
+            .enumerate()
+            .take_while(|(n, i)| **i - n * (DIGIT_PLACEHOLDER.len() - 1) < start)
+            .count();
+        e.offset(shift * (DIGIT_PLACEHOLDER.len() - 1))
+    })
+}
+
+impl DigitKeyBindings {
+    /// Empty key bindings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a key binding with the placeholder, e.g. `"Alt+<digit>"`
+    pub fn push(&mut self, template: &str) -> Result<(), ParseKeyBindingError> {
+        let mut sequences = Vec::with_capacity(10);
+        for digit in 0..10 {
+            sequences.push(expand(template, digit)?);
+        }
+        for (kbs, seq) in self.digits.iter_mut().zip(sequences) {
+            kbs.push(seq);
+        }
+        self.templates.push(template.to_string());
+        Ok(())
+    }
+
+    /// The key bindings with the placeholder
+    pub fn templates(&self) -> impl Iterator<Item = &str> {
+        self.templates.iter().map(String::as_str)
+    }
+
+    /// The key bindings of the digit, `None` if it is not a single digit
+    pub fn get(&self, digit: u8) -> Option<&KeyBindings> {
+        self.digits.get(digit as usize)
+    }
+}
+
+impl Serialize for DigitKeyBindings {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.templates.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DigitKeyBindings {
+    fn deserialize<D>(deserializer: D) -> Result<DigitKeyBindings, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut key_bindings = DigitKeyBindings::new();
+        for template in Vec::<String>::deserialize(deserializer)? {
+            key_bindings.push(&template).map_err(serde::de::Error::custom)?;
+        }
+        Ok(key_bindings)
+    }
+}
+
+impl fmt::Debug for DigitKeyBindings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(&self.templates).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "crossterm_0_28_1")]
+    use crossterm_0_28_1::event::{KeyCode, KeyEvent, KeyModifiers};
+    #[cfg(feature = "crossterm_0_29_0")]
+    use crossterm_0_29_0::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    #[derive(Debug, Deserialize, Serialize)]
+    struct DigitKeyBindingsConfig {
+        kbs: DigitKeyBindings,
+    }
+
+    #[test]
+    fn expand_digit_key_bindings() {
+        let config: DigitKeyBindingsConfig =
+            toml::from_str(r#"kbs = ["Alt+<digit>", "g <digit>"]"#).unwrap();
+        assert_eq!(config.kbs.templates().collect::<Vec<_>>(), vec!["Alt+<digit>", "g <digit>"]);
+        let alt_3 = KeyEvent::new(KeyCode::Char('3'), KeyModifiers::ALT);
+        assert!(config.kbs.get(3).is_some_and(|kbs| kbs.match_any(&alt_3)));
+        assert!(!config.kbs.get(4).is_some_and(|kbs| kbs.match_any(&alt_3)));
+        assert_eq!(config.kbs.get(7).map(ToString::to_string).as_deref(), Some("⎇7|g 7"));
+        assert!(config.kbs.get(10).is_none());
+        assert_eq!(
+            toml::to_string(&config).unwrap(),
+            "kbs = [\"Alt+<digit>\", \"g <digit>\"]\n"
+        );
+
+        let mut kbs = DigitKeyBindings::new();
+        assert_eq!(
+            kbs.push("Alt+1"),
+            Err(ParseKeyBindingError::MissingPlaceholder { span: 0..5 })
+        );
+        assert!(matches!(
+            kbs.push("Contrl+<digit>"),
+            Err(ParseKeyBindingError::UnknownModifier { span, .. }) if span == (0..6)
+        ));
+        assert!(matches!(
+            kbs.push("g <digit> Hme"),
+            Err(ParseKeyBindingError::UnknownKey { span, .. }) if span == (10..13)
+        ));
+        assert!(kbs.templates().next().is_none());
+    }
+}
@@ -0,0 +1,167 @@
+use std::fmt;
+use std::ops::Range;
+
+/// The error of parsing a key binding string, e.g. `"Contrl+PgUp"`
+///
+/// The span is the byte range of the offending substring in the parsed string.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseKeyBindingError {
+    /// There is no key in the key binding, e.g. `""` or `"Control+"`
+    EmptyKey { span: Range<usize> },
+
+    /// The modifier is not one of `Shift`, `Control`, `Alternate`, `Super`, `Hyper`, `Meta`
+    UnknownModifier {
+        name: String,
+        span: Range<usize>,
+        suggestion: Option<&'static str>,
+    },
+
+    /// The key is neither a char nor a key name
+    UnknownKey {
+        name: String,
+        span: Range<usize>,
+        suggestion: Option<&'static str>,
+    },
+
+    /// The list of key bindings is not in the format of `["Control+c", "q"]`
+    MalformedList { span: Range<usize> },
+
+    /// The `when` condition is not in the format of `"listFocused && !readOnly"`
+    MalformedCondition { span: Range<usize> },
+
+    /// The key binding of an event with a digit has no `<digit>` placeholder, e.g. `"Alt+1"`
+    MissingPlaceholder { span: Range<usize> },
+}
+
+impl ParseKeyBindingError {
+    /// The byte range of the offending substring
+    pub fn span(&self) -> Range<usize> {
+        match self {
+            ParseKeyBindingError::EmptyKey { span }
+            | ParseKeyBindingError::MalformedList { span }
+            | ParseKeyBindingError::MalformedCondition { span }
+            | ParseKeyBindingError::MissingPlaceholder { span }
+            | ParseKeyBindingError::UnknownModifier { span, .. }
+            | ParseKeyBindingError::UnknownKey { span, .. } => span.clone(),
+        }
+    }
+
+    /// The most similar valid name of the offending substring
+    pub fn suggestion(&self) -> Option<&'static str> {
+        match self {
+            ParseKeyBindingError::EmptyKey { .. }
+            | ParseKeyBindingError::MalformedList { .. }
+            | ParseKeyBindingError::MalformedCondition { .. }
+            | ParseKeyBindingError::MissingPlaceholder { .. } => None,
+            ParseKeyBindingError::UnknownModifier { suggestion, .. }
+            | ParseKeyBindingError::UnknownKey { suggestion, .. } => *suggestion,
+        }
+    }
+
+    /// Move the span when the parsed string is a part of a longer one
+    pub(crate) fn offset(mut self, n: usize) -> Self {
+        match &mut self {
+            ParseKeyBindingError::EmptyKey { span }
+            | ParseKeyBindingError::MalformedList { span }
+            | ParseKeyBindingError::MalformedCondition { span }
+            | ParseKeyBindingError::MissingPlaceholder { span }
+            | ParseKeyBindingError::UnknownModifier { span, .. }
+            | ParseKeyBindingError::UnknownKey { span, .. } => {
+                *span = span.start + n..span.end + n;
+            }
+        }
+        self
+    }
+
+    /// Replace the span, e.g. when the parsed string is unescaped from a longer one
+    pub(crate) fn with_span(mut self, new_span: Range<usize>) -> Self {
+        match &mut self {
+            ParseKeyBindingError::EmptyKey { span }
+            | ParseKeyBindingError::MalformedList { span }
+            | ParseKeyBindingError::MalformedCondition { span }
+            | ParseKeyBindingError::MissingPlaceholder { span }
+            | ParseKeyBindingError::UnknownModifier { span, .. }
+            | ParseKeyBindingError::UnknownKey { span, .. } => *span = new_span,
+        }
+        self
+    }
+}
+
+impl fmt::Display for ParseKeyBindingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseKeyBindingError::EmptyKey { span } => {
+                write!(f, "missing key at {}..{}", span.start, span.end)?
+            }
+            ParseKeyBindingError::MalformedList { span } => write!(
+                f,
+                "malformed list of key bindings at {}..{}, please use the format [\"Control+c\", \"q\"]",
+                span.start, span.end
+            )?,
+            ParseKeyBindingError::MalformedCondition { span } => write!(
+                f,
+                "malformed condition at {}..{}, please use flags with !, &&, || and parentheses, e.g. \"listFocused && !readOnly\"",
+                span.start, span.end
+            )?,
+            ParseKeyBindingError::MissingPlaceholder { span } => write!(
+                f,
+                "missing placeholder at {}..{}, please bind the digits with <digit>, e.g. \"Alt+<digit>\"",
+                span.start, span.end
+            )?,
+            ParseKeyBindingError::UnknownModifier { name, span, .. } => write!(
+                f,
+                "unknown modifier `{name}` at {}..{}, please use one of Shift, Control, Alternate, Super, Hyper, Meta",
+                span.start, span.end
+            )?,
+            ParseKeyBindingError::UnknownKey { name, span, .. } => write!(
+                f,
+                "unknown key `{name}` at {}..{}, please use a char or a key name",
+                span.start, span.end
+            )?,
+        }
+        if let Some(suggestion) = self.suggestion() {
+            write!(f, ", did you mean `{suggestion}`?")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseKeyBindingError {}
+
+/// Levenshtein distance between two strings, ignoring ASCII case
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().map(|c| c.to_ascii_lowercase()).collect();
+    let b: Vec<char> = b.chars().map(|c| c.to_ascii_lowercase()).collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut curr = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == cb { prev[j] } else { prev[j] + 1 };
+            curr.push(cost.min(prev[j + 1] + 1).min(curr[j] + 1));
+        }
+        prev = curr;
+    }
+    prev[b.len()]
+}
+
+/// Check the name is an abbreviation of the candidate, ignoring ASCII case
+fn is_abbreviation(name: &str, candidate: &str) -> bool {
+    let mut candidate = candidate.chars().map(|c| c.to_ascii_lowercase());
+    name.chars()
+        .map(|c| c.to_ascii_lowercase())
+        .all(|c| candidate.any(|cc| cc == c))
+}
+
+/// The most similar name within a small edit distance, abbreviations are preferred on ties
+pub(crate) fn suggest(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'static str>,
+) -> Option<&'static str> {
+    let threshold = (name.chars().count() / 3).max(2);
+    candidates
+        .into_iter()
+        .map(|c| (edit_distance(name, c), !is_abbreviation(name, c), c))
+        .filter(|(d, _, _)| *d <= threshold)
+        .min_by_key(|(d, not_abbreviation, _)| (*d, *not_abbreviation))
+        .map(|(_, _, c)| c)
+}
@@ -0,0 +1,118 @@
+#[cfg(feature = "crossterm_0_28_1")]
+use crossterm_0_28_1::event::KeyEvent;
+#[cfg(feature = "crossterm_0_29_0")]
+use crossterm_0_29_0::event::KeyEvent;
+
+use crate::legacy::legacy_fold;
+use crate::{normalize_shift, KeyBinding, KeyBindings, KeySequence};
+use std::collections::HashMap;
+
+/// An index from key strokes to the positions of the actions bound to them, such that a key event
+/// is dispatched with a hash lookup instead of matching the key bindings of all actions.
+///
+/// The key strokes are indexed in the normalized form used by [`KeyBinding::matches`], and the
+/// index keeps the form folded for legacy terminals as well, so
+/// [`KeyBindingIndex::set_legacy_mode`] can be switched after the index is built.
+/// Like [`KeyBindings::match_any`], key sequences with more than one key stroke are not indexed.
+#[derive(Clone, Debug, Default)]
+pub struct KeyBindingIndex {
+    positions: HashMap<KeyBinding, Vec<usize>>,
+    legacy_positions: HashMap<KeyBinding, Vec<usize>>,
+    legacy: bool,
+}
+
+fn normalized(kb: &KeyBinding) -> KeyBinding {
+    let (code, modifiers) = normalize_shift(kb.code, kb.modifiers);
+    KeyBinding {
+        code,
+        modifiers,
+        kind: kb.kind,
+    }
+}
+
+fn legacy_normalized(kb: &KeyBinding) -> KeyBinding {
+    let (code, modifiers) = legacy_fold(kb.code, kb.modifiers);
+    let (code, modifiers) = normalize_shift(code, modifiers);
+    KeyBinding {
+        code,
+        modifiers,
+        kind: kb.kind,
+    }
+}
+
+/// Add the position once, positions are inserted in ascending order
+fn insert(positions: &mut HashMap<KeyBinding, Vec<usize>>, kb: KeyBinding, position: usize) {
+    let positions = positions.entry(kb).or_default();
+    if positions.last() != Some(&position) {
+        positions.push(position);
+    }
+}
+
+impl KeyBindingIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index the key bindings of the action at the position.
+    ///
+    /// Please insert the actions in ascending order of positions, then the positions of a key
+    /// stroke are in the same order.
+    pub fn insert(&mut self, position: usize, key_bindings: &KeyBindings) {
+        for kb in key_bindings.iter().filter_map(KeySequence::single) {
+            insert(&mut self.positions, normalized(kb), position);
+            insert(&mut self.legacy_positions, legacy_normalized(kb), position);
+        }
+    }
+
+    /// Set the terminal is in legacy mode, i.e. the keyboard enhancement flags of crossterm are not
+    /// pushed, such that the key bindings legacy terminals can not distinguish are folded into the
+    /// same key when looking up, e.g. `"Control+i"` and `"Tab"`.
+    pub fn set_legacy_mode(&mut self, enabled: bool) {
+        self.legacy = enabled;
+    }
+
+    /// The index is in legacy mode or not, see [`KeyBindingIndex::set_legacy_mode`]
+    pub fn legacy_mode(&self) -> bool {
+        self.legacy
+    }
+
+    /// The positions of the actions matching the key event
+    pub fn get(&self, key_event: &KeyEvent) -> &[usize] {
+        let kb = KeyBinding::from(*key_event);
+        let found = if self.legacy {
+            self.legacy_positions.get(&legacy_normalized(&kb))
+        } else {
+            self.positions.get(&normalized(&kb))
+        };
+        found.map(Vec::as_slice).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "crossterm_0_28_1")]
+    use crossterm_0_28_1::event::{KeyCode, KeyEventKind, KeyModifiers};
+    #[cfg(feature = "crossterm_0_29_0")]
+    use crossterm_0_29_0::event::{KeyCode, KeyEventKind, KeyModifiers};
+
+    #[test]
+    fn lookup_key_binding_index() {
+        let mut index = KeyBindingIndex::new();
+        index.insert(0, &r#"["Control+c", "q", "g g"]"#.parse().unwrap());
+        index.insert(1, &r#"["Control+i", "Release:q"]"#.parse().unwrap());
+        index.insert(2, &r#"["q", "Tab"]"#.parse().unwrap());
+
+        let q = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE);
+        assert_eq!(index.get(&q), &[0, 2]);
+        let q_release =
+            KeyEvent::new_with_kind(KeyCode::Char('q'), KeyModifiers::NONE, KeyEventKind::Release);
+        assert_eq!(index.get(&q_release), &[1]);
+        let g = KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE);
+        assert!(index.get(&g).is_empty());
+
+        let tab = KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE);
+        assert_eq!(index.get(&tab), &[2]);
+    }
+}
@@ -0,0 +1,96 @@
+#[cfg(feature = "crossterm_0_29_0")]
+use crossterm_0_29_0::event::{KeyCode, KeyModifiers};
+#[cfg(feature = "crossterm_0_28_1")]
+use crossterm_0_28_1::event::{KeyCode, KeyModifiers};
+
+use crate::{KeyBinding, KeyBindings};
+
+/// Fold the key into the one reported by legacy terminals, e.g. `Control+i` is reported as `Tab`
+pub(crate) fn legacy_fold(code: KeyCode, modifiers: KeyModifiers) -> (KeyCode, KeyModifiers) {
+    if !modifiers.contains(KeyModifiers::CONTROL) {
+        return (code, modifiers);
+    }
+    let folded = match code {
+        KeyCode::Char('i') => KeyCode::Tab,
+        KeyCode::Char('m') => KeyCode::Enter,
+        KeyCode::Char('[') => KeyCode::Esc,
+        KeyCode::Char('h') => KeyCode::Backspace,
+        _ => return (code, modifiers),
+    };
+    (folded, modifiers - KeyModifiers::CONTROL)
+}
+
+impl KeyBinding {
+    /// The key reported by legacy terminals for this key binding if it is a different key,
+    /// e.g. `Tab` for `"Control+i"`
+    pub fn legacy_equivalent(&self) -> Option<KeyBinding> {
+        let (code, modifiers) = legacy_fold(self.code, self.modifiers);
+        (code != self.code).then_some(KeyBinding {
+            code,
+            modifiers,
+            kind: self.kind,
+        })
+    }
+}
+
+impl KeyBindings {
+    /// The key bindings which can not be distinguished in legacy mode, i.e. the keyboard
+    /// enhancement flags of crossterm are not pushed, e.g. `"Control+i"` reported as `Tab`, with
+    /// the keys reported by legacy terminals
+    pub fn legacy_ambiguities(&self) -> Vec<(KeyBinding, KeyBinding)> {
+        self.sequences()
+            .iter()
+            .flat_map(|seq| seq.key_bindings())
+            .filter_map(|kb| kb.legacy_equivalent().map(|reported_as| (kb.clone(), reported_as)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_key_sequence;
+
+    #[cfg(feature = "crossterm_0_29_0")]
+    use crossterm_0_29_0::event::KeyEvent;
+    #[cfg(feature = "crossterm_0_28_1")]
+    use crossterm_0_28_1::event::KeyEvent;
+
+    #[test]
+    fn fold_legacy_keys() {
+        let kbs = KeyBindings(vec![
+            parse_key_sequence("Control+i").unwrap(),
+            parse_key_sequence("Control+m").unwrap(),
+            parse_key_sequence("Esc").unwrap(),
+            parse_key_sequence("Control+Alt+h").unwrap(),
+        ]);
+        let tab = KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE);
+        let ctrl_i = KeyEvent::new(KeyCode::Char('i'), KeyModifiers::CONTROL);
+        let ctrl_bracket = KeyEvent::new(KeyCode::Char('['), KeyModifiers::CONTROL);
+        let alt_backspace = KeyEvent::new(KeyCode::Backspace, KeyModifiers::ALT);
+
+        assert!(!kbs.match_any(&tab));
+        assert!(kbs.match_any(&ctrl_i));
+        assert!(!kbs.match_any(&ctrl_bracket));
+
+        let mut index = crate::KeyBindingIndex::new();
+        index.insert(0, &kbs);
+        assert!(index.get(&tab).is_empty());
+
+        index.set_legacy_mode(true);
+        assert_eq!(index.get(&tab), &[0]);
+        assert_eq!(index.get(&alt_backspace), &[0]);
+        let legacy = |key_event| kbs.match_any_with_legacy(key_event, &(), true);
+        assert!(legacy(&tab));
+        assert!(legacy(&ctrl_i));
+        assert!(legacy(&ctrl_bracket));
+        assert!(legacy(&alt_backspace));
+        let ambiguities = kbs.legacy_ambiguities();
+
+        assert_eq!(ambiguities.len(), 3);
+        assert_eq!(ambiguities[0].0.code, KeyCode::Char('i'));
+        assert_eq!(ambiguities[0].1.code, KeyCode::Tab);
+        assert_eq!(ambiguities[2].1.code, KeyCode::Backspace);
+        assert_eq!(ambiguities[2].1.modifiers, KeyModifiers::ALT);
+    }
+}
@@ -1,12 +1,29 @@
 #[cfg(feature = "crossterm_0_29_0")]
-use crossterm_0_29_0::event::{KeyCode, KeyEvent, KeyModifiers, MediaKeyCode};
+use crossterm_0_29_0::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MediaKeyCode, ModifierKeyCode};
 #[cfg(feature = "crossterm_0_28_1")]
-use crossterm_0_28_1::event::{KeyCode, KeyEvent, KeyModifiers, MediaKeyCode};
+use crossterm_0_28_1::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MediaKeyCode, ModifierKeyCode};
 
-use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+mod digit;
+mod error;
+mod index;
+mod legacy;
+mod sequence;
+mod when;
+
+pub use digit::{DigitKeyBindings, DIGIT_PLACEHOLDER};
+pub use error::ParseKeyBindingError;
+pub use index::KeyBindingIndex;
+pub use sequence::{KeySequenceMatcher, SequenceMatch};
+pub use when::{KeyContext, When};
+
+use serde::ser::SerializeMap;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
 use std::fmt;
-#[cfg(feature = "case_ignore")]
-use str_utils::StartsWithIgnoreAsciiCase;
+use std::ops::Range;
+use std::str::FromStr;
+use toml::de::DeValue;
+use toml::Spanned;
 
 #[derive(Default, PartialEq)]
 pub enum DisplayFormat {
@@ -24,10 +41,130 @@ pub enum DisplayFormat {
     Abbreviation
 }
 
-#[derive(PartialEq)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct KeyBinding {
     pub code: KeyCode,
     pub modifiers: KeyModifiers,
+    /// The kind of key event to match, `Press` by default, written as a prefix in the config
+    /// format, e.g. `"Release:Space"` or `"Repeat:j"`
+    pub kind: KeyEventKind,
+}
+
+fn str_to_kind(s: &str) -> Option<KeyEventKind> {
+    let s = s.trim();
+    #[cfg(feature = "case_ignore")]
+    let eq = |name: &str| s.eq_ignore_ascii_case(name);
+    #[cfg(not(feature = "case_ignore"))]
+    let eq = |name: &str| s == name;
+
+    if eq("Press") {
+        Some(KeyEventKind::Press)
+    } else if eq("Repeat") {
+        Some(KeyEventKind::Repeat)
+    } else if eq("Release") {
+        Some(KeyEventKind::Release)
+    } else {
+        None
+    }
+}
+
+/// Render the kind of a key binding, the default `Press` kind is omitted
+fn kind_prefix(kind: KeyEventKind) -> &'static str {
+    match kind {
+        KeyEventKind::Press => "",
+        KeyEventKind::Repeat => "Repeat:",
+        KeyEventKind::Release => "Release:",
+    }
+}
+
+/// KeyModifiers in the canonical order used when serializing and displaying a key binding
+const MODIFIERS: [KeyModifiers; 6] = [
+    KeyModifiers::CONTROL,
+    KeyModifiers::ALT,
+    KeyModifiers::SHIFT,
+    KeyModifiers::SUPER,
+    KeyModifiers::HYPER,
+    KeyModifiers::META,
+];
+
+fn str_to_modifier(s: &str) -> Option<KeyModifiers> {
+    let s = s.trim();
+    #[cfg(feature = "case_ignore")]
+    let eq = |name: &str| s.eq_ignore_ascii_case(name);
+    #[cfg(not(feature = "case_ignore"))]
+    let eq = |name: &str| s == name;
+
+    if eq("Shift") {
+        Some(KeyModifiers::SHIFT)
+    } else if eq("Control") || eq("Ctrl") {
+        Some(KeyModifiers::CONTROL)
+    } else if eq("Alternate") || eq("Alt") {
+        Some(KeyModifiers::ALT)
+    } else if eq("Super") {
+        Some(KeyModifiers::SUPER)
+    } else if eq("Hyper") {
+        Some(KeyModifiers::HYPER)
+    } else if eq("Meta") {
+        Some(KeyModifiers::META)
+    } else {
+        None
+    }
+}
+
+/// Render the modifiers of a key binding in the canonical order, including the trailing `+`
+/// except for the symbol format
+fn modifiers_prefix(modifiers: KeyModifiers, f: &DisplayFormat) -> String {
+    let mut prefix = String::new();
+    for modifier in MODIFIERS.iter().filter(|m| modifiers.contains(**m)) {
+        let name = match (f, *modifier) {
+            (DisplayFormat::Symbols, KeyModifiers::SHIFT) => "\u{21e7}", //⇧
+            (DisplayFormat::Symbols, KeyModifiers::CONTROL) => "^",
+            (DisplayFormat::Symbols, KeyModifiers::ALT) => "\u{2387}", //⎇
+            (DisplayFormat::Symbols, KeyModifiers::SUPER) => "\u{2756}", //❖
+            (DisplayFormat::Symbols, KeyModifiers::HYPER) => "\u{2388}", //⎈
+            (DisplayFormat::Symbols, KeyModifiers::META) => "\u{2318}", //⌘
+            (DisplayFormat::Abbreviation, KeyModifiers::CONTROL) => "Ctrl",
+            (DisplayFormat::Abbreviation, KeyModifiers::ALT) => "Alt",
+            (_, KeyModifiers::SHIFT) => "Shift",
+            (_, KeyModifiers::CONTROL) => "Control",
+            (_, KeyModifiers::ALT) => "Alternate",
+            (_, KeyModifiers::SUPER) => "Super",
+            (_, KeyModifiers::HYPER) => "Hyper",
+            (_, KeyModifiers::META) => "Meta",
+            (_, _) => continue,
+        };
+        prefix.push_str(name);
+        if *f != DisplayFormat::Symbols {
+            prefix.push('+');
+        }
+    }
+    prefix
+}
+
+/// The name of the key in the config format, the chars taken as separators by the parser are
+/// spelled out or escaped, e.g. `"Space"` or `"U00A0"`
+fn keycode_to_string(code: &KeyCode) -> String {
+    match code {
+        KeyCode::Char(c) if *c != ' ' && *c != '+' && c.is_whitespace() => {
+            format!("U{:04X}", *c as u32)
+        }
+        KeyCode::Char(c) if *c != ' ' && *c != '+' => c.to_string(),
+        KeyCode::F(n) => format!("F{n}"),
+        code => NAMED_KEYS
+            .iter()
+            .find(|(_, named)| named == code)
+            .map_or_else(String::new, |(name, _)| name.to_string()),
+    }
+}
+
+impl KeyBinding {
+    /// The key stroke in the config format, e.g. `"Control+c"`
+    fn to_config_string(&self) -> String {
+        let mut s = kind_prefix(self.kind).to_string();
+        s.push_str(&modifiers_prefix(self.modifiers, &DisplayFormat::Full));
+        s.push_str(&keycode_to_string(&self.code));
+        s
+    }
 }
 
 impl Serialize for KeyBinding {
@@ -35,155 +172,172 @@ impl Serialize for KeyBinding {
     where
         S: Serializer,
     {
-        let mut s = match self.modifiers {
-            KeyModifiers::SHIFT => "Shift+".to_string(),
-            KeyModifiers::CONTROL => "Control+".to_string(),
-            KeyModifiers::ALT => "Alternate+".to_string(),
-            KeyModifiers::SUPER => "Super+".to_string(),
-            KeyModifiers::HYPER => "Hyper+".to_string(),
-            KeyModifiers::META => "Meta+".to_string(),
-            // TODO support more KeyModifiers
-            _ => String::new(),
-        };
-        match self.code {
-            KeyCode::Char(c) => s.push(c),
-            KeyCode::Backspace => s.push_str("Backspace"),
-            KeyCode::Enter => s.push_str("Enter"),
-            KeyCode::Left => s.push_str("Left"),
-            KeyCode::Right => s.push_str("Right"),
-            KeyCode::Up => s.push_str("Up"),
-            KeyCode::Down => s.push_str("Down"),
-            KeyCode::Home => s.push_str("Home"),
-            KeyCode::End => s.push_str("End"),
-            KeyCode::PageUp => s.push_str("PageUp"),
-            KeyCode::PageDown => s.push_str("PageDown"),
-            KeyCode::Tab => s.push_str("Tab"),
-            KeyCode::BackTab => s.push_str("BackTab"),
-            KeyCode::Delete => s.push_str("Delete"),
-            KeyCode::Insert => s.push_str("Insert"),
-            KeyCode::F(n) => s.push_str(&format!("F{n}")),
-            KeyCode::Esc => s.push_str("Esc"),
-            KeyCode::CapsLock => s.push_str("CapsLock"),
-            KeyCode::ScrollLock => s.push_str("ScrollLock"),
-            KeyCode::NumLock => s.push_str("NumLock"),
-            KeyCode::PrintScreen => s.push_str("PrintScreen"),
-            KeyCode::Pause => s.push_str("Pause"),
-            KeyCode::Menu => s.push_str("Menu"),
-            KeyCode::KeypadBegin => s.push_str("KeypadBegin"),
-            KeyCode::Media(media_keycode) => s.push_str(&media_keycode.to_string()),
-            _ => {
-                return Err(ser::Error::custom(format!(
-                    "Unsupported KeyCode: {:}",
-                    self.code
-                )))
-            }
-        }
-        serializer.serialize_str(&s)
+        serializer.serialize_str(&self.to_config_string())
     }
 }
 
-fn str_to_keycode(s: &str) -> KeyCode {
+/// The names of modifiers in the config format
+const MODIFIER_NAMES: [&str; 8] = [
+    "Shift",
+    "Control",
+    "Ctrl",
+    "Alternate",
+    "Alt",
+    "Super",
+    "Hyper",
+    "Meta",
+];
+
+/// The names of keys in the config format besides chars and `F1` ~ `F255`, the first name of a
+/// key is the one written back
+const NAMED_KEYS: [(&str, KeyCode); 54] = [
+    ("Space", KeyCode::Char(' ')),
+    ("Plus", KeyCode::Char('+')),
+    ("Minus", KeyCode::Char('-')),
+    ("Comma", KeyCode::Char(',')),
+    ("Backspace", KeyCode::Backspace),
+    ("Enter", KeyCode::Enter),
+    ("Left", KeyCode::Left),
+    ("Right", KeyCode::Right),
+    ("Up", KeyCode::Up),
+    ("Down", KeyCode::Down),
+    ("Home", KeyCode::Home),
+    ("End", KeyCode::End),
+    ("PageUp", KeyCode::PageUp),
+    ("PageDown", KeyCode::PageDown),
+    ("Tab", KeyCode::Tab),
+    ("BackTab", KeyCode::BackTab),
+    ("Delete", KeyCode::Delete),
+    ("Insert", KeyCode::Insert),
+    ("Null", KeyCode::Null),
+    ("Esc", KeyCode::Esc),
+    ("CapsLock", KeyCode::CapsLock),
+    ("ScrollLock", KeyCode::ScrollLock),
+    ("NumLock", KeyCode::NumLock),
+    ("PrintScreen", KeyCode::PrintScreen),
+    ("Pause", KeyCode::Pause),
+    ("Menu", KeyCode::Menu),
+    ("KeypadBegin", KeyCode::KeypadBegin),
+    ("Play", KeyCode::Media(MediaKeyCode::Play)),
+    ("MediaPause", KeyCode::Media(MediaKeyCode::Pause)),
+    ("PlayPause", KeyCode::Media(MediaKeyCode::PlayPause)),
+    ("Reverse", KeyCode::Media(MediaKeyCode::Reverse)),
+    ("Stop", KeyCode::Media(MediaKeyCode::Stop)),
+    ("FastForward", KeyCode::Media(MediaKeyCode::FastForward)),
+    ("Rewind", KeyCode::Media(MediaKeyCode::Rewind)),
+    ("TrackNext", KeyCode::Media(MediaKeyCode::TrackNext)),
+    ("TrackPrevious", KeyCode::Media(MediaKeyCode::TrackPrevious)),
+    ("Record", KeyCode::Media(MediaKeyCode::Record)),
+    ("LowerVolume", KeyCode::Media(MediaKeyCode::LowerVolume)),
+    ("RaiseVolume", KeyCode::Media(MediaKeyCode::RaiseVolume)),
+    ("MuteVolume", KeyCode::Media(MediaKeyCode::MuteVolume)),
+    ("LeftShift", KeyCode::Modifier(ModifierKeyCode::LeftShift)),
+    ("LeftControl", KeyCode::Modifier(ModifierKeyCode::LeftControl)),
+    ("LeftAlt", KeyCode::Modifier(ModifierKeyCode::LeftAlt)),
+    ("LeftSuper", KeyCode::Modifier(ModifierKeyCode::LeftSuper)),
+    ("LeftHyper", KeyCode::Modifier(ModifierKeyCode::LeftHyper)),
+    ("LeftMeta", KeyCode::Modifier(ModifierKeyCode::LeftMeta)),
+    ("RightShift", KeyCode::Modifier(ModifierKeyCode::RightShift)),
+    ("RightControl", KeyCode::Modifier(ModifierKeyCode::RightControl)),
+    ("RightAlt", KeyCode::Modifier(ModifierKeyCode::RightAlt)),
+    ("RightSuper", KeyCode::Modifier(ModifierKeyCode::RightSuper)),
+    ("RightHyper", KeyCode::Modifier(ModifierKeyCode::RightHyper)),
+    ("RightMeta", KeyCode::Modifier(ModifierKeyCode::RightMeta)),
+    ("IsoLevel3Shift", KeyCode::Modifier(ModifierKeyCode::IsoLevel3Shift)),
+    ("IsoLevel5Shift", KeyCode::Modifier(ModifierKeyCode::IsoLevel5Shift)),
+];
+
+/// A few function keys listed for suggestions besides the named keys
+const FUNCTION_KEY_NAMES: [&str; 3] = ["F1", "F12", "F24"];
+
+fn str_to_keycode(s: &str) -> Option<KeyCode> {
     // Interpreting a single whitespace as Space
     if s == " " {
-        return KeyCode::Char(' ');
+        return Some(KeyCode::Char(' '));
     }
     let s = s.trim();
-    if s.len() == 1 {
-        KeyCode::Char(s.chars().next().unwrap())
-    } else if s == "Backspace" {
-        KeyCode::Backspace
-    } else if s == "Enter" {
-        KeyCode::Enter
-    } else if s == "Left" {
-        KeyCode::Left
-    } else if s == "Right" {
-        KeyCode::Right
-    } else if s == "Up" {
-        KeyCode::Up
-    } else if s == "Down" {
-        KeyCode::Down
-    } else if s == "Home" {
-        KeyCode::Home
-    } else if s == "End" {
-        KeyCode::End
-    } else if s == "PageUp" {
-        KeyCode::PageUp
-    } else if s == "PageDown" {
-        KeyCode::PageDown
-    } else if s == "Tab" {
-        KeyCode::Tab
-    } else if s == "BackTab" {
-        KeyCode::BackTab
-    } else if s == "Delete" {
-        KeyCode::Delete
-    } else if s == "Insert" {
-        KeyCode::Insert
-    } else if s == "F1" {
-        KeyCode::F(1)
-    } else if s == "F2" {
-        KeyCode::F(2)
-    } else if s == "F3" {
-        KeyCode::F(3)
-    } else if s == "F4" {
-        KeyCode::F(4)
-    } else if s == "F5" {
-        KeyCode::F(5)
-    } else if s == "F6" {
-        KeyCode::F(6)
-    } else if s == "F7" {
-        KeyCode::F(7)
-    } else if s == "F8" {
-        KeyCode::F(8)
-    } else if s == "F9" {
-        KeyCode::F(9)
-    } else if s == "F10" {
-        KeyCode::F(10)
-    } else if s == "F11" {
-        KeyCode::F(11)
-    } else if s == "F12" {
-        KeyCode::F(12)
-    } else if s == "Esc" {
-        KeyCode::Esc
-    } else if s == "CapsLock" {
-        KeyCode::CapsLock
-    } else if s == "ScrollLock" {
-        KeyCode::ScrollLock
-    } else if s == "NumLock" {
-        KeyCode::NumLock
-    } else if s == "PrintScreen" {
-        KeyCode::PrintScreen
-    } else if s == "Pause" {
-        KeyCode::Pause
-    } else if s == "Menu" {
-        KeyCode::Menu
-    } else if s == "KeypadBegin" {
-        KeyCode::KeypadBegin
-    } else if s == "Play" {
-        KeyCode::Media(MediaKeyCode::Play)
-    } else if s == "PlayPause" {
-        KeyCode::Media(MediaKeyCode::PlayPause)
-    } else if s == "Reverse" {
-        KeyCode::Media(MediaKeyCode::Reverse)
-    } else if s == "Stop" {
-        KeyCode::Media(MediaKeyCode::Stop)
-    } else if s == "FastForward" {
-        KeyCode::Media(MediaKeyCode::FastForward)
-    } else if s == "Rewind" {
-        KeyCode::Media(MediaKeyCode::Rewind)
-    } else if s == "TrackNext" {
-        KeyCode::Media(MediaKeyCode::TrackNext)
-    } else if s == "TrackPrevious" {
-        KeyCode::Media(MediaKeyCode::TrackPrevious)
-    } else if s == "Record" {
-        KeyCode::Media(MediaKeyCode::Record)
-    } else if s == "LowerVolume" {
-        KeyCode::Media(MediaKeyCode::LowerVolume)
-    } else if s == "RaiseVolume" {
-        KeyCode::Media(MediaKeyCode::RaiseVolume)
-    } else if s == "MuteVolume" {
-        KeyCode::Media(MediaKeyCode::MuteVolume)
+    let mut chars = s.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(c));
+    }
+    // `F0` is not on keyboards but crossterm can report it
+    if let Some(n) = s.strip_prefix('F').and_then(|n| n.parse::<u8>().ok()) {
+        return Some(KeyCode::F(n));
+    }
+    // an escaped char, e.g. `"U00A0"`
+    if let Some(hex) = s.strip_prefix('U').filter(|hex| hex.len() >= 4) {
+        if let Some(c) = u32::from_str_radix(hex, 16).ok().and_then(char::from_u32) {
+            return Some(KeyCode::Char(c));
+        }
+    }
+    NAMED_KEYS
+        .iter()
+        .find(|(name, _)| *name == s)
+        .map(|(_, code)| *code)
+}
+
+/// Parse a single key stroke of the config format, e.g. `"Control+c"`
+fn parse_key_binding(input: &str) -> Result<KeyBinding, ParseKeyBindingError> {
+    let span_of = |part: &str| {
+        let start = part.as_ptr() as usize - input.as_ptr() as usize;
+        start..start + part.len()
+    };
+    let mut key_bindings = KeyBinding {
+        code: KeyCode::Null,
+        modifiers: KeyModifiers::NONE,
+        kind: KeyEventKind::Press,
+    };
+
+    let mut s = input;
+    if let Some((kind, key)) = s.split_once(':') {
+        if let Some(kind) = str_to_kind(kind) {
+            key_bindings.kind = kind;
+            s = key;
+        }
+    }
+
+    // A trailing `+` is the key itself, e.g. `"+"` or `"Control++"`
+    let (modifiers, code) = if s.trim() == "+" {
+        ("", "+")
+    } else if let Some(modifiers) = s.trim_end().strip_suffix("++") {
+        (modifiers, "+")
+    } else if let Some((modifiers, code)) = s.rsplit_once('+') {
+        (modifiers, code)
+    } else {
+        ("", s)
+    };
+
+    if !modifiers.is_empty() {
+        for part in modifiers.split('+') {
+            if let Some(modifier) = str_to_modifier(part) {
+                key_bindings.modifiers |= modifier;
+            } else {
+                let name = part.trim();
+                return Err(ParseKeyBindingError::UnknownModifier {
+                    name: name.to_string(),
+                    span: span_of(name),
+                    suggestion: error::suggest(name, MODIFIER_NAMES),
+                });
+            }
+        }
+    }
+    if let Some(code) = str_to_keycode(code) {
+        key_bindings.code = code;
+        Ok(key_bindings)
+    } else if code.trim().is_empty() {
+        Err(ParseKeyBindingError::EmptyKey {
+            span: span_of(code),
+        })
     } else {
-        KeyCode::Null
+        let name = code.trim();
+        Err(ParseKeyBindingError::UnknownKey {
+            name: name.to_string(),
+            span: span_of(name),
+            suggestion: error::suggest(
+                name,
+                NAMED_KEYS.iter().map(|(name, _)| *name).chain(FUNCTION_KEY_NAMES),
+            ),
+        })
     }
 }
 
@@ -192,69 +346,8 @@ impl<'de> Deserialize<'de> for KeyBinding {
     where
         D: Deserializer<'de>,
     {
-        let mut key_bindings = KeyBinding {
-            code: KeyCode::Null,
-            modifiers: KeyModifiers::NONE,
-        };
-        let mut error = None;
-
-        <String as Deserialize>::deserialize(deserializer).map(|s| {
-            if s.contains('+') {
-                #[cfg(feature = "case_ignore")]
-                if s.starts_with_ignore_ascii_case("Shift") {
-                    key_bindings.modifiers = KeyModifiers::SHIFT;
-                } else if s.starts_with_ignore_ascii_case("Control") || s.starts_with_ignore_ascii_case("Ctrl") {
-                    key_bindings.modifiers = KeyModifiers::CONTROL;
-                } else if s.starts_with_ignore_ascii_case("Alternate") || s.starts_with_ignore_ascii_case("Alt") {
-                    key_bindings.modifiers = KeyModifiers::ALT;
-                } else if s.starts_with_ignore_ascii_case("Super") {
-                    key_bindings.modifiers = KeyModifiers::SUPER;
-                } else if s.starts_with_ignore_ascii_case("Hyper") {
-                    key_bindings.modifiers = KeyModifiers::HYPER;
-                } else if s.starts_with_ignore_ascii_case("Meta") {
-                    key_bindings.modifiers = KeyModifiers::META;
-                } else {
-                    error = Some(de::Error::custom(
-                        "Currently only support following KeyModifiers: Shift, Control, Alternate, Super, Hyper, Meta"
-                    ));
-                }
-                #[cfg(not(feature = "case_ignore"))]
-                if s.starts_with("Shift") {
-                    key_bindings.modifiers = KeyModifiers::SHIFT;
-                } else if s.starts_with("Control") || s.starts_with("Ctrl") {
-                    key_bindings.modifiers = KeyModifiers::CONTROL;
-                } else if s.starts_with("Alternate") || s.starts_with("Alt") {
-                    key_bindings.modifiers = KeyModifiers::ALT;
-                } else if s.starts_with("Super") {
-                    key_bindings.modifiers = KeyModifiers::SUPER;
-                } else if s.starts_with("Hyper") {
-                    key_bindings.modifiers = KeyModifiers::HYPER;
-                } else if s.starts_with("Meta") {
-                    key_bindings.modifiers = KeyModifiers::META;
-                } else {
-                    error = Some(de::Error::custom(
-                        "Currently only support following KeyModifiers: Shift, Control, Alternate, Super, Hyper, Meta"
-                    ));
-                }
-                let mut splitter = s.splitn(2, '+');
-                key_bindings.code = str_to_keycode(splitter.nth(1).unwrap());
-            } else {
-                key_bindings.code = str_to_keycode(&s);
-            }
-        })?;
-        if let Some(e) = error {
-            Err(e)
-        } else if key_bindings.code == KeyCode::Null {
-            Err(de::Error::custom(
-                r#"Can not load a KeyCode, please use a char or one of following KeyCodes:
-"Backspace", "Enter", "Left", "Right", "Up", "Down", "Home", "End", "PageUp", "PageDown", "Tab", "BackTab", "Delete",
-"Insert", "F1" ~ "F12", "Esc", "CapsLock", "ScrollLock", "NumLock", "PrintScreen", "Pause", "Menu", "KeypadBegin",
-"Play", "PlayPause", "Reverse", "Stop", "FastForward", "Rewind", "TrackNext", "TrackPrevious", "Record", "LowerVolume", 
-"RaiseVolume", "MuteVolume""#,
-            ))
-        } else {
-            Ok(key_bindings)
-        }
+        let s = <String as Deserialize>::deserialize(deserializer)?;
+        parse_key_binding(&s).map_err(de::Error::custom)
     }
 }
 
@@ -263,16 +356,8 @@ impl<'de> Deserialize<'de> for KeyBinding {
 // ex: Canadian Multilingual Layout, Truly Ergonomic Keyboard
 impl fmt::Display for KeyBinding {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self.modifiers {
-            KeyModifiers::SHIFT => write!(f, "\u{21e7}")?, //⇧
-            KeyModifiers::CONTROL => write!(f, "^")?,
-            KeyModifiers::ALT => write!(f, "\u{2387}")?, //⎇
-            KeyModifiers::SUPER => write!(f, "\u{2756}")?, //❖
-            KeyModifiers::HYPER => write!(f, "\u{2388}")?, //⎈
-            KeyModifiers::META => write!(f, "\u{2318}")?, //⌘
-            KeyModifiers::NONE => write!(f, "")?,
-            _ => write!(f, "?")?,
-        };
+        write!(f, "{}", kind_prefix(self.kind))?;
+        write!(f, "{}", modifiers_prefix(self.modifiers, &DisplayFormat::Symbols))?;
         match self.code {
             KeyCode::Char(c) => write!(f, "{}", c),
             KeyCode::Backspace => write!(f, "\u{232b}"), //⌫
@@ -310,7 +395,8 @@ impl fmt::Display for KeyBinding {
             KeyCode::Media(MediaKeyCode::LowerVolume) => write!(f, "\u{1F508}"), //🔈
             KeyCode::Media(MediaKeyCode::RaiseVolume) => write!(f, "\u{1F50A}"), //🔊
             KeyCode::Media(MediaKeyCode::MuteVolume) => write!(f, "\u{1F507}"), //🔇
-            _ => write!(f, "?"),
+            KeyCode::Media(MediaKeyCode::Pause) => write!(f, "\u{23F8}"), //⏸
+            code => write!(f, "{}", keycode_to_string(&code)),
         }
     }
 }
@@ -318,136 +404,537 @@ impl fmt::Display for KeyBinding {
 impl fmt::Debug for KeyBinding {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f,"\"")?;
-        match self.modifiers {
-            KeyModifiers::SHIFT => write!(f, "Shift+")?,
-            KeyModifiers::CONTROL => write!(f, "Control+")?,
-            KeyModifiers::ALT => write!(f, "Alternate+")?,
-            KeyModifiers::SUPER => write!(f, "Super+")?,
-            KeyModifiers::HYPER => write!(f, "Hyper+")?,
-            KeyModifiers::META => write!(f, "Meta+")?,
-            KeyModifiers::NONE => write!(f, "")?,
-            _ => write!(f, "UNKNOWN+")?,
-        };
+        self.fmt_config(f)?;
+        write!(f,"\"")
+    }
+}
+
+impl KeyBinding {
+    /// Write the key stroke as the config format without quotes, the quote and the backslash are
+    /// escaped
+    fn fmt_config(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", kind_prefix(self.kind))?;
+        write!(f, "{}", modifiers_prefix(self.modifiers, &DisplayFormat::Debug))?;
         match self.code {
-            KeyCode::Char(c) => write!(f, "{}", c)?,
-            KeyCode::Backspace => write!(f, "Backspace")?,
-            KeyCode::Enter => write!(f, "Enter")?,
-            KeyCode::Left => write!(f, "Left")?,
-            KeyCode::Right => write!(f, "Right")?,
-            KeyCode::Up => write!(f, "Up")?,
-            KeyCode::Down => write!(f, "Down")?,
-            KeyCode::Home => write!(f, "Home")?,
-            KeyCode::End => write!(f, "End")?,
-            KeyCode::PageUp => write!(f, "PageUp")?,
-            KeyCode::PageDown => write!(f, "PageDown")?,
-            KeyCode::Tab => write!(f, "Tab")?,
-            KeyCode::BackTab => write!(f, "BackTab")?,
-            KeyCode::Delete => write!(f, "Delete")?,
-            KeyCode::Insert => write!(f, "Insert")?,
-            KeyCode::F(n) => write!(f, "F{}", n)?,
-            KeyCode::Esc => write!(f, "Esc")?,
-            KeyCode::CapsLock => write!(f, "CapsLock")?,
-            KeyCode::ScrollLock => write!(f, "ScrollLock")?,
-            KeyCode::NumLock => write!(f, "NumLock")?,
-            KeyCode::PrintScreen => write!(f, "PrintScreen")?,
-            KeyCode::Pause => write!(f, "Pause")?,
-            KeyCode::Menu => write!(f, "Menu")?,
-            KeyCode::KeypadBegin => write!(f, "KeypadBegin")?,
-            KeyCode::Media(MediaKeyCode::Play) => write!(f, "Play")?,
-            KeyCode::Media(MediaKeyCode::PlayPause) => write!(f, "PlayPause")?,
-            KeyCode::Media(MediaKeyCode::Reverse) => write!(f, "Reverse")?,
-            KeyCode::Media(MediaKeyCode::Stop) => write!(f, "Stop")?,
-            KeyCode::Media(MediaKeyCode::FastForward) => write!(f, "FastForward")?,
-            KeyCode::Media(MediaKeyCode::Rewind) => write!(f, "Rewind")?,
-            KeyCode::Media(MediaKeyCode::TrackNext) => write!(f, "TrackNext")?,
-            KeyCode::Media(MediaKeyCode::TrackPrevious) => write!(f, "TrackPrevious")?,
-            KeyCode::Media(MediaKeyCode::Record) => write!(f, "Record")?,
-            KeyCode::Media(MediaKeyCode::LowerVolume) => write!(f, "LowerVolume")?,
-            KeyCode::Media(MediaKeyCode::RaiseVolume) => write!(f, "RaiseVolume")?,
-            KeyCode::Media(MediaKeyCode::MuteVolume) => write!(f, "MuteVolume")?,
-            _ => write!(f, "?")?,
+            KeyCode::Char(c @ ('"' | '\\')) => write!(f, "\\{c}")?,
+            code => write!(f, "{}", keycode_to_string(&code))?,
         }
-        write!(f,"\"")
+        Ok(())
+    }
+}
+
+/// The symbols typed with Shift on a US layout
+#[cfg(feature = "shift_normalize")]
+const SHIFTED_SYMBOLS: &str = "~!@#$%^&*()_+{}|:\"<>?";
+
+/// Terminals disagree on reporting Shift with chars, some report `'A'` with `SHIFT` and others
+/// report `'A'` without modifiers. The normalized form of a letter is the uppercase char with
+/// `SHIFT`, and the symbols typed with Shift on a US layout, e.g. `'?'`, already show the Shift so
+/// the `SHIFT` is dropped. The other chars keep the `SHIFT`, e.g. `"Shift+1"` is not `"1"`.
+#[cfg(feature = "shift_normalize")]
+pub(crate) fn normalize_shift(code: KeyCode, modifiers: KeyModifiers) -> (KeyCode, KeyModifiers) {
+    match code {
+        KeyCode::Char(c) if c.is_uppercase() => (code, modifiers | KeyModifiers::SHIFT),
+        KeyCode::Char(c) if c.is_lowercase() && modifiers.contains(KeyModifiers::SHIFT) => {
+            let mut upper = c.to_uppercase();
+            match (upper.next(), upper.next()) {
+                (Some(u), None) => (KeyCode::Char(u), modifiers),
+                _ => (code, modifiers),
+            }
+        }
+        KeyCode::Char(c) if SHIFTED_SYMBOLS.contains(c) => (code, modifiers - KeyModifiers::SHIFT),
+        _ => (code, modifiers),
     }
 }
 
+#[cfg(not(feature = "shift_normalize"))]
+pub(crate) fn normalize_shift(code: KeyCode, modifiers: KeyModifiers) -> (KeyCode, KeyModifiers) {
+    (code, modifiers)
+}
+
+/// The form of a key used when matching, folded into the key reported by legacy terminals in
+/// legacy mode
+pub(crate) fn normalize(code: KeyCode, modifiers: KeyModifiers, legacy: bool) -> (KeyCode, KeyModifiers) {
+    let (code, modifiers) = if legacy {
+        legacy::legacy_fold(code, modifiers)
+    } else {
+        (code, modifiers)
+    };
+    normalize_shift(code, modifiers)
+}
+
 impl KeyBinding {
+    /// A key binding matching the key press
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        KeyBinding {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+        }
+    }
+
+    /// Parse a single key stroke of the config format, e.g. `"Control+c"`
+    pub fn parse(s: &str) -> Result<KeyBinding, ParseKeyBindingError> {
+        parse_key_binding(s)
+    }
+
+    /// Match the key event with this key stroke
+    pub fn matches(&self, key_event: &KeyEvent) -> bool {
+        self.matches_with_legacy(key_event, false)
+    }
+
+    /// Match the key event with this key stroke, in legacy mode the keys legacy terminals can not
+    /// distinguish are folded into the same key, e.g. `"Control+i"` matches `Tab`
+    pub fn matches_with_legacy(&self, key_event: &KeyEvent, legacy: bool) -> bool {
+        normalize(self.code, self.modifiers, legacy) == normalize(key_event.code, key_event.modifiers, legacy)
+            && self.kind == key_event.kind
+    }
+
     pub fn display(&self, f: &DisplayFormat) -> String {
         match f {
             DisplayFormat::Symbols => format!("{}", self),
             DisplayFormat::Debug => format!("{:?}", self),
             DisplayFormat::Full | DisplayFormat::Abbreviation =>  {
-                let mut display = match (f, self.modifiers) {
-                    (_, KeyModifiers::SHIFT) => "Shift+".to_string(),
-                    (DisplayFormat::Full, KeyModifiers::CONTROL) => "Control+".to_string(),
-                    (DisplayFormat::Abbreviation, KeyModifiers::CONTROL) => "Ctrl+".to_string(),
-                    (DisplayFormat::Full, KeyModifiers::ALT) => "Alternate+".to_string(),
-                    (DisplayFormat::Abbreviation, KeyModifiers::ALT) => "Alt+".to_string(),
-                    (_, KeyModifiers::SUPER) => "Super+".to_string(),
-                    (_, KeyModifiers::HYPER) => "Hyper+".to_string(),
-                    (_, KeyModifiers::META) =>  "Meta+".to_string(),
-                    (_, KeyModifiers::NONE) => String::new(),
-                    (_, _) => "UNKNOWN+".to_string(),
-                };
-                match self.code {
-                    KeyCode::Char(' ') => display.push_str("Space"),
-                    KeyCode::Char(c) => display.push(c),
-                    KeyCode::Backspace => display.push_str("Backspace"),
-                    KeyCode::Enter => display.push_str("Enter"),
-                    KeyCode::Left => display.push_str("Left"),
-                    KeyCode::Right => display.push_str("Right"),
-                    KeyCode::Up => display.push_str("Up"),
-                    KeyCode::Down => display.push_str("Down"),
-                    KeyCode::Home => display.push_str("Home"),
-                    KeyCode::End => display.push_str("End"),
-                    KeyCode::PageUp => display.push_str("PageUp"),
-                    KeyCode::PageDown => display.push_str("PageDown"),
-                    KeyCode::Tab => display.push_str("Tab"),
-                    KeyCode::BackTab => display.push_str("BackTab"),
-                    KeyCode::Delete => display.push_str("Delete"),
-                    KeyCode::Insert => display.push_str("Insert"),
-                    KeyCode::F(n) => display.push_str(&format!("F{}", n)),
-                    KeyCode::Esc => display.push_str("Esc"),
-                    KeyCode::CapsLock => display.push_str("CapsLock"),
-                    KeyCode::ScrollLock => display.push_str("ScrollLock"),
-                    KeyCode::NumLock => display.push_str("NumLock"),
-                    KeyCode::PrintScreen => display.push_str("PrintScreen"),
-                    KeyCode::Pause => display.push_str("Pause"),
-                    KeyCode::Menu => display.push_str("Menu"),
-                    KeyCode::KeypadBegin => display.push_str("KeypadBegin"),
-                    KeyCode::Media(MediaKeyCode::Play) => display.push_str("Play"),
-                    KeyCode::Media(MediaKeyCode::PlayPause) => display.push_str("PlayPause"),
-                    KeyCode::Media(MediaKeyCode::Reverse) => display.push_str("Reverse"),
-                    KeyCode::Media(MediaKeyCode::Stop) => display.push_str("Stop"),
-                    KeyCode::Media(MediaKeyCode::FastForward) => display.push_str("FastForward"),
-                    KeyCode::Media(MediaKeyCode::Rewind) => display.push_str("Rewind"),
-                    KeyCode::Media(MediaKeyCode::TrackNext) => display.push_str("TrackNext"),
-                    KeyCode::Media(MediaKeyCode::TrackPrevious) => display.push_str("TrackPrevious"),
-                    KeyCode::Media(MediaKeyCode::Record) => display.push_str("Record"),
-                    KeyCode::Media(MediaKeyCode::LowerVolume) => display.push_str("LowerVolume"),
-                    KeyCode::Media(MediaKeyCode::RaiseVolume) => display.push_str("RaiseVolume"),
-                    KeyCode::Media(MediaKeyCode::MuteVolume) => display.push_str("MuteVolume"),
-                    _ => display.push('?'),
-                }
+                let mut display = kind_prefix(self.kind).to_string();
+                display.push_str(&modifiers_prefix(self.modifiers, f));
+                display.push_str(&keycode_to_string(&self.code));
                 display
             }
         }
     }
 }
 
+// crossterm only implements `PartialOrd` for the key types, the orders are total for the key codes
+// and the kinds, and the modifiers are ordered by their bits.
+impl Ord for KeyBinding {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.code
+            .partial_cmp(&other.code)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| self.modifiers.bits().cmp(&other.modifiers.bits()))
+            .then_with(|| self.kind.partial_cmp(&other.kind).unwrap_or(Ordering::Equal))
+    }
+}
+
+impl PartialOrd for KeyBinding {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A sequence of key strokes, which are separated by whitespace in the config format,
+/// e.g. `"g g"` or `"Control+x Control+s"`
+///
+/// The sequence can have a condition in the structured config format, e.g.
+/// `{ key = "Enter", when = "listFocused && !readOnly" }`, such that it only matches when the
+/// condition holds in the context of the application.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct KeySequence {
+    strokes: Vec<KeyBinding>,
+    when: Option<When>,
+}
+
+impl KeySequence {
+    pub fn new(key_bindings: Vec<KeyBinding>) -> Self {
+        KeySequence {
+            strokes: key_bindings,
+            when: None,
+        }
+    }
+
+    /// The sequence only matching when the condition holds
+    pub fn with_when(mut self, when: When) -> Self {
+        self.when = Some(when);
+        self
+    }
+
+    /// The condition of the sequence
+    pub fn when(&self) -> Option<&When> {
+        self.when.as_ref()
+    }
+
+    /// The condition of the sequence holds in the context, or there is no condition
+    pub fn holds(&self, ctx: &dyn KeyContext) -> bool {
+        self.when.as_ref().is_none_or(|when| when.holds(ctx))
+    }
+
+    /// Parse key strokes separated by whitespace of the config format, e.g. `"g g"`
+    pub fn parse(s: &str) -> Result<KeySequence, ParseKeyBindingError> {
+        parse_key_sequence(s)
+    }
+
+    /// The key strokes of the sequence
+    pub fn key_bindings(&self) -> &[KeyBinding] {
+        &self.strokes
+    }
+
+    /// The key stroke if the sequence only has one key stroke
+    pub fn single(&self) -> Option<&KeyBinding> {
+        match self.strokes.as_slice() {
+            [key_binding] => Some(key_binding),
+            _ => None,
+        }
+    }
+
+    /// The sequences are matched by the same key strokes, e.g. `"Shift+a"` and `"A"`, the
+    /// conditions are not compared
+    pub fn overlaps(&self, other: &KeySequence) -> bool {
+        self.strokes.len() == other.strokes.len() && self.overlaps_start_of(other)
+    }
+
+    /// The sequence is matched by the first key strokes of a longer sequence, e.g. `"g"` of
+    /// `"g g"`, the conditions are not compared
+    pub fn is_prefix_of(&self, other: &KeySequence) -> bool {
+        self.strokes.len() < other.strokes.len() && self.overlaps_start_of(other)
+    }
+
+    fn overlaps_start_of(&self, other: &KeySequence) -> bool {
+        self.strokes.iter().zip(other.strokes.iter()).all(|(a, b)| {
+            normalize(a.code, a.modifiers, false) == normalize(b.code, b.modifiers, false) && a.kind == b.kind
+        })
+    }
+
+    pub fn display(&self, f: &DisplayFormat) -> String {
+        match f {
+            DisplayFormat::Symbols => format!("{}", self),
+            DisplayFormat::Debug => format!("{:?}", self),
+            _ => self
+                .strokes
+                .iter()
+                .map(|kb| kb.display(f))
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+}
+
+impl From<KeyBinding> for KeySequence {
+    fn from(key_binding: KeyBinding) -> Self {
+        KeySequence::new(vec![key_binding])
+    }
+}
+
+impl From<KeyEvent> for KeyBinding {
+    fn from(key_event: KeyEvent) -> Self {
+        KeyBinding {
+            code: key_event.code,
+            modifiers: key_event.modifiers,
+            kind: key_event.kind,
+        }
+    }
+}
+
+impl From<KeyEvent> for KeySequence {
+    fn from(key_event: KeyEvent) -> Self {
+        KeyBinding::from(key_event).into()
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = ParseKeyBindingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_key_binding(s)
+    }
+}
+
+impl FromStr for KeySequence {
+    type Err = ParseKeyBindingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_key_sequence(s)
+    }
+}
+
+impl Serialize for KeySequence {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut strokes = Vec::new();
+        for kb in self.strokes.iter() {
+            strokes.push(kb.to_config_string());
+        }
+        if let Some(when) = &self.when {
+            let mut map = serializer.serialize_map(Some(2))?;
+            map.serialize_entry("key", &strokes.join(" "))?;
+            map.serialize_entry("when", &when.to_string())?;
+            map.end()
+        } else {
+            serializer.serialize_str(&strokes.join(" "))
+        }
+    }
+}
+
+/// Split a key sequence into the key strokes and their offsets, a whitespace right after the `+`
+/// of a modifier is the key itself rather than a separator, e.g. `"Control+ "` for Control+Space
+fn split_key_strokes(s: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut start = None;
+    let mut chars = s.char_indices().peekable();
+    std::iter::from_fn(move || {
+        while let Some((i, c)) = chars.next() {
+            let begin = *start.get_or_insert(i);
+            let stroke = &s[begin..i];
+            let is_key = stroke.len() > 1 && stroke.ends_with('+') && !stroke.ends_with("++");
+            if c.is_whitespace() && !is_key {
+                start = None;
+                if !stroke.is_empty() {
+                    return Some((begin, stroke));
+                }
+            } else if chars.peek().is_none() {
+                start = None;
+                return Some((begin, &s[begin..]));
+            }
+        }
+        None
+    })
+}
+
+/// Parse key strokes separated by whitespace, e.g. `"g g"`
+fn parse_key_sequence(s: &str) -> Result<KeySequence, ParseKeyBindingError> {
+    // Interpreting a single whitespace as Space
+    if s == " " {
+        return parse_key_binding(s).map(KeySequence::from);
+    }
+    let strokes = split_key_strokes(s)
+        .map(|(offset, stroke)| parse_key_binding(stroke).map_err(|e| e.offset(offset)))
+        .collect::<Result<Vec<_>, _>>()?;
+    if strokes.is_empty() {
+        Err(ParseKeyBindingError::EmptyKey { span: 0..s.len() })
+    } else {
+        Ok(KeySequence::new(strokes))
+    }
+}
+
+impl<'de> Deserialize<'de> for KeySequence {
+    fn deserialize<D>(deserializer: D) -> Result<KeySequence, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(KeySequenceVisitor)
+    }
+}
+
+/// Deserialize a key sequence from a string, e.g. `"g g"`, or a table with a condition, e.g.
+/// `{ key = "Enter", when = "listFocused" }`
+struct KeySequenceVisitor;
+
+impl<'de> de::Visitor<'de> for KeySequenceVisitor {
+    type Value = KeySequence;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a key binding or a table of `key` and `when`")
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<KeySequence, E> {
+        parse_key_sequence(s).map_err(de::Error::custom)
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<KeySequence, A::Error> {
+        let mut key = None;
+        let mut when = None;
+        while let Some(field) = map.next_key::<String>()? {
+            match field.as_str() {
+                "key" => key = Some(map.next_value::<String>()?),
+                "when" => when = Some(map.next_value::<String>()?),
+                _ => return Err(de::Error::unknown_field(&field, &["key", "when"])),
+            }
+        }
+        let key = key.ok_or_else(|| de::Error::missing_field("key"))?;
+        let mut seq = parse_key_sequence(&key).map_err(de::Error::custom)?;
+        if let Some(when) = when {
+            seq.when = Some(When::parse(&when).map_err(de::Error::custom)?);
+        }
+        Ok(seq)
+    }
+}
+
+impl fmt::Display for KeySequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, kb) in self.strokes.iter().enumerate() {
+            if i > 0 {
+                write!(f, " {}", kb)?;
+            } else {
+                write!(f, "{}", kb)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for KeySequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.when.is_some() {
+            write!(f, "{{ key = ")?;
+        }
+        write!(f, "\"")?;
+        for (i, kb) in self.strokes.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            kb.fmt_config(f)?;
+        }
+        write!(f, "\"")?;
+        if let Some(when) = &self.when {
+            write!(f, ", when = {:?} }}", when)?;
+        }
+        Ok(())
+    }
+}
+
 /// KeyBindings struct for key bind configure
-#[derive(Serialize, Deserialize, PartialEq)]
-pub struct KeyBindings(Vec<KeyBinding>);
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct KeyBindings(Vec<KeySequence>);
+
+/// Parse a list of key bindings in the TOML array format, e.g. `["Control+c", "q"]`, or a single
+/// key binding without brackets.
+/// The spans of errors are accurate if the strings in the list have no escape, else they cover the
+/// whole string.
+fn parse_key_bindings(s: &str) -> Result<KeyBindings, ParseKeyBindingError> {
+    if !s.trim_start().starts_with('[') {
+        return parse_key_sequence(s).map(|seq| KeyBindings(vec![seq]));
+    }
+    let list = DeValue::parse(s).map_err(|e| ParseKeyBindingError::MalformedList {
+        span: e.span().unwrap_or(0..s.len()),
+    })?;
+    let DeValue::Array(items) = list.get_ref() else {
+        return Err(ParseKeyBindingError::MalformedList { span: list.span() });
+    };
+    items
+        .iter()
+        .map(|item| parse_list_item(s, item))
+        .collect::<Result<Vec<_>, _>>()
+        .map(KeyBindings)
+}
+
+/// Parse a key binding in a list, a string, e.g. `"g g"`, or a table with a condition, e.g.
+/// `{ key = "Enter", when = "listFocused" }`
+fn parse_list_item(s: &str, item: &Spanned<DeValue>) -> Result<KeySequence, ParseKeyBindingError> {
+    let malformed = |span: Range<usize>| ParseKeyBindingError::MalformedList { span };
+    let table = match item.get_ref() {
+        DeValue::String(key) => return parse_in_list(s, key, item.span(), parse_key_sequence),
+        DeValue::Table(table) => table,
+        _ => return Err(malformed(item.span())),
+    };
+    let mut key = None;
+    let mut when = None;
+    for (name, value) in table.iter() {
+        let DeValue::String(v) = value.get_ref() else {
+            return Err(malformed(value.span()));
+        };
+        match name.get_ref().as_ref() {
+            "key" => key = Some((v, value.span())),
+            "when" => when = Some((v, value.span())),
+            _ => return Err(malformed(name.span())),
+        }
+    }
+    let Some((key, span)) = key else {
+        return Err(malformed(item.span()));
+    };
+    let mut seq = parse_in_list(s, key, span, parse_key_sequence)?;
+    if let Some((when, span)) = when {
+        seq.when = Some(parse_in_list(s, when, span, When::parse)?);
+    }
+    Ok(seq)
+}
+
+/// Parse a string of a list at the span, the errors are moved into the list
+fn parse_in_list<T>(
+    s: &str,
+    value: &str,
+    span: Range<usize>,
+    parse: impl Fn(&str) -> Result<T, ParseKeyBindingError>,
+) -> Result<T, ParseKeyBindingError> {
+    parse(value).map_err(|e| {
+        if s[span.clone()].len() == value.len() + 2 {
+            // a string without escapes, the value is right after the quote
+            e.offset(span.start + 1)
+        } else {
+            e.with_span(span)
+        }
+    })
+}
+
+impl FromStr for KeyBindings {
+    type Err = ParseKeyBindingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_key_bindings(s)
+    }
+}
+
+impl From<Vec<KeySequence>> for KeyBindings {
+    fn from(sequences: Vec<KeySequence>) -> Self {
+        KeyBindings(sequences)
+    }
+}
+
+impl FromIterator<KeySequence> for KeyBindings {
+    fn from_iter<I: IntoIterator<Item = KeySequence>>(iter: I) -> Self {
+        KeyBindings(iter.into_iter().collect())
+    }
+}
 
 impl KeyBindings {
-    /// Match one of key bindings
-    pub fn match_any(&self, key_event: &KeyEvent) -> bool {
-        for key_bind in self.0.iter() {
-            if key_bind.code == key_event.code && key_bind.modifiers == key_event.modifiers {
-                return true;
-            }
+    /// Empty key bindings
+    pub const fn new() -> Self {
+        KeyBindings(Vec::new())
+    }
+
+    /// Iterate the key sequences of the key bindings
+    pub fn iter(&self) -> impl Iterator<Item = &KeySequence> {
+        self.0.iter()
+    }
+
+    /// Add a key binding or a key sequence if it is not bound yet
+    pub fn push(&mut self, key_binding: impl Into<KeySequence>) {
+        let sequence = key_binding.into();
+        if !self.0.contains(&sequence) {
+            self.0.push(sequence);
         }
-        false
+    }
+
+    /// Remove a key binding or a key sequence, return `true` if it was bound
+    pub fn remove(&mut self, key_binding: impl Into<KeySequence>) -> bool {
+        let sequence = key_binding.into();
+        let len = self.0.len();
+        self.0.retain(|seq| *seq != sequence);
+        self.0.len() != len
+    }
+
+    /// Check a key binding or a key sequence is bound
+    pub fn contains(&self, key_binding: impl Into<KeySequence>) -> bool {
+        self.0.contains(&key_binding.into())
+    }
+
+    /// Match one of key bindings, key sequences with more than one key stroke are only matched by
+    /// [`KeySequenceMatcher`].
+    /// The conditions of key bindings are evaluated in the empty context, where no flag is set.
+    pub fn match_any(&self, key_event: &KeyEvent) -> bool {
+        self.match_any_with(key_event, &())
+    }
+
+    /// Match one of key bindings whose condition holds in the context
+    pub fn match_any_with(&self, key_event: &KeyEvent, ctx: &dyn KeyContext) -> bool {
+        self.match_any_with_legacy(key_event, ctx, false)
+    }
+
+    /// Match one of key bindings whose condition holds in the context, in legacy mode the keys
+    /// legacy terminals can not distinguish are folded into the same key
+    pub fn match_any_with_legacy(&self, key_event: &KeyEvent, ctx: &dyn KeyContext, legacy: bool) -> bool {
+        self.0.iter().any(|seq| {
+            seq.single().is_some_and(|kb| kb.matches_with_legacy(key_event, legacy)) && seq.holds(ctx)
+        })
+    }
+
+    /// Any key binding has a condition
+    pub fn has_conditions(&self) -> bool {
+        self.0.iter().any(|seq| seq.when.is_some())
+    }
+
+    /// The key sequences of the key bindings
+    pub fn sequences(&self) -> &[KeySequence] {
+        &self.0
     }
 }
 
@@ -525,11 +1012,8 @@ mod tests {
         let serialized = toml::to_string(&t).unwrap();
         assert_eq!(serialized, "kb = \"Q\"\n");
 
-        let serialized = toml::to_string(&only_modifiers);
-        assert_eq!(
-            serialized,
-            Err(ser::Error::custom("Unsupported KeyCode: Null"))
-        );
+        let serialized = toml::to_string(&only_modifiers).unwrap();
+        assert_eq!(serialized, "kb = \"Alternate+Null\"\n");
 
         let serialized = toml::to_string(&t_with_esc).unwrap();
         assert_eq!(serialized, "kb = \"Esc\"\n");
@@ -579,6 +1063,29 @@ mod tests {
         assert_eq!(t_with_esc.kb.display(&DisplayFormat::Full), "Esc");
     }
 
+    #[test]
+    fn combined_modifiers_keybinding_config() {
+        let desered_t: T = toml::from_str("kb = \"Shift+Control+p\"\n").unwrap();
+        assert_eq!(desered_t.kb.code, KeyCode::Char('p'));
+        assert_eq!(desered_t.kb.modifiers, KeyModifiers::CONTROL | KeyModifiers::SHIFT);
+        assert_eq!(toml::to_string(&desered_t).unwrap(), "kb = \"Control+Shift+p\"\n");
+        assert_eq!(format!("{}", desered_t.kb), "^⇧p");
+        assert_eq!(format!("{:?}", desered_t.kb), "\"Control+Shift+p\"");
+        assert_eq!(desered_t.kb.display(&DisplayFormat::Full), "Control+Shift+p");
+        assert_eq!(desered_t.kb.display(&DisplayFormat::Abbreviation), "Ctrl+Shift+p");
+
+        let desered_t: T = toml::from_str("kb = \"Ctrl+Alt+Delete\"\n").unwrap();
+        assert_eq!(desered_t.kb.code, KeyCode::Delete);
+        assert_eq!(desered_t.kb.modifiers, KeyModifiers::CONTROL | KeyModifiers::ALT);
+        assert_eq!(desered_t.kb.display(&DisplayFormat::Abbreviation), "Ctrl+Alt+Delete");
+
+        let desered_t: T = toml::from_str("kb = \"Super+Shift+Left\"\n").unwrap();
+        assert_eq!(desered_t.kb.modifiers, KeyModifiers::SHIFT | KeyModifiers::SUPER);
+        assert_eq!(toml::to_string(&desered_t).unwrap(), "kb = \"Shift+Super+Left\"\n");
+
+        assert!(toml::from_str::<T>("kb = \"Control+Foo+x\"\n").is_err());
+    }
+
     #[test]
     fn ser_keybindings_config() {
         let config = keybindings_config();
@@ -595,6 +1102,341 @@ mod tests {
         assert_eq!(config.kbs.display(&DisplayFormat::Abbreviation), "Ctrl+c | Q");
     }
 
+    #[test]
+    fn key_sequences_config() {
+        let desered: U = toml::from_str("kbs = [\"g g\", \"Ctrl+x Control+s\", \" \"]\n").unwrap();
+        assert_eq!(desered.kbs.sequences()[0].key_bindings().len(), 2);
+        assert_eq!(desered.kbs.sequences()[2].single().unwrap().code, KeyCode::Char(' '));
+        assert_eq!(format!("{}", desered.kbs), "g g|^x ^s| ");
+        assert_eq!(format!("{:?}", desered.kbs), "[\"g g\", \"Control+x Control+s\", \"Space\"]");
+        assert_eq!(desered.kbs.display(&DisplayFormat::Abbreviation), "g g | Ctrl+x Ctrl+s | Space");
+
+        let ctrl_x = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::CONTROL);
+        assert!(!desered.kbs.match_any(&ctrl_x));
+
+        let ctrl_x_ctrl_s: KeySequence = "Control+x Control+s".parse().unwrap();
+        assert!(desered.kbs.sequences()[1].overlaps(&ctrl_x_ctrl_s));
+        assert!(!desered.kbs.sequences()[0].overlaps(&"g".parse().unwrap()));
+        assert!("g".parse::<KeySequence>().unwrap().is_prefix_of(&desered.kbs.sequences()[0]));
+        assert!(!desered.kbs.sequences()[0].is_prefix_of(&desered.kbs.sequences()[0]));
+
+        assert!(toml::from_str::<U>("kbs = [\"\"]\n").is_err());
+
+        // a whitespace after a modifier is the key rather than a separator, as before sequences
+        let desered: U =
+            toml::from_str("kbs = [\"Control+ \", \"Shift+ \", \"Control++ x\", \"g  Control+ \"]\n").unwrap();
+        assert_eq!(
+            format!("{:?}", desered.kbs),
+            "[\"Control+Space\", \"Shift+Space\", \"Control+Plus x\", \"g Control+Space\"]"
+        );
+        let ctrl_space = KeyEvent::new(KeyCode::Char(' '), KeyModifiers::CONTROL);
+        assert!(desered.kbs.match_any(&ctrl_space));
+    }
+
+    #[test]
+    fn conditional_keybinding_config() {
+        let desered: U = toml::from_str(
+            "kbs = [\"Esc\", { key = \"Enter\", when = \"listFocused && !readOnly\" }]\n",
+        )
+        .unwrap();
+        assert_eq!(desered.kbs.sequences()[0].when(), None);
+        assert_eq!(
+            desered.kbs.sequences()[1].when().map(|w| w.to_string()).as_deref(),
+            Some("listFocused && !readOnly")
+        );
+        assert_eq!(
+            format!("{:?}", desered.kbs),
+            "[\"Esc\", { key = \"Enter\", when = \"listFocused && !readOnly\" }]"
+        );
+        assert_eq!(desered.kbs.display(&DisplayFormat::Full), "Esc | Enter");
+        assert_eq!(
+            toml::to_string(&desered).unwrap(),
+            "kbs = [\"Esc\", { key = \"Enter\", when = \"listFocused && !readOnly\" }]\n"
+        );
+
+        let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+        assert!(desered.kbs.has_conditions());
+        assert!(!desered.kbs.match_any(&enter));
+        assert!(desered.kbs.match_any_with(&enter, &["listFocused"]));
+        assert!(!desered.kbs.match_any_with(&enter, &["listFocused", "readOnly"]));
+
+        assert!(toml::from_str::<U>("kbs = [{ when = \"a\" }]\n").is_err());
+        assert!(toml::from_str::<U>("kbs = [{ key = \"a\", when = \"a &&\" }]\n").is_err());
+    }
+
+    #[test]
+    fn separator_keybinding_config() {
+        for (config, code, modifiers, serialized) in [
+            ("+", KeyCode::Char('+'), KeyModifiers::NONE, "Plus"),
+            ("Plus", KeyCode::Char('+'), KeyModifiers::NONE, "Plus"),
+            ("Control++", KeyCode::Char('+'), KeyModifiers::CONTROL, "Control+Plus"),
+            ("Control+Plus", KeyCode::Char('+'), KeyModifiers::CONTROL, "Control+Plus"),
+            ("Shift+=", KeyCode::Char('='), KeyModifiers::SHIFT, "Shift+="),
+            ("Control+Minus", KeyCode::Char('-'), KeyModifiers::CONTROL, "Control+-"),
+            ("Alt+Comma", KeyCode::Char(','), KeyModifiers::ALT, "Alternate+,"),
+            ("Control+Space", KeyCode::Char(' '), KeyModifiers::CONTROL, "Control+Space"),
+        ] {
+            let desered_t: T = toml::from_str(&format!("kb = \"{config}\"\n")).unwrap();
+            assert_eq!(desered_t.kb.code, code, "parse {config}");
+            assert_eq!(desered_t.kb.modifiers, modifiers, "parse {config}");
+            assert_eq!(
+                toml::to_string(&desered_t).unwrap(),
+                format!("kb = \"{serialized}\"\n")
+            );
+            assert_eq!(desered_t.kb.display(&DisplayFormat::Full), serialized);
+        }
+
+        let desered: U = toml::from_str("kbs = [\"Control++ Control+Minus\"]\n").unwrap();
+        assert_eq!(desered.kbs.sequences()[0].key_bindings().len(), 2);
+
+        assert!(toml::from_str::<T>("kb = \"Control+\"\n").is_err());
+    }
+
+    #[test]
+    fn all_keycodes_keybinding_config() {
+        for (config, code) in [
+            ("F0", KeyCode::F(0)),
+            ("F1", KeyCode::F(1)),
+            ("F13", KeyCode::F(13)),
+            ("F24", KeyCode::F(24)),
+            ("Null", KeyCode::Null),
+            ("é", KeyCode::Char('é')),
+            ("MediaPause", KeyCode::Media(MediaKeyCode::Pause)),
+            ("PlayPause", KeyCode::Media(MediaKeyCode::PlayPause)),
+            ("FastForward", KeyCode::Media(MediaKeyCode::FastForward)),
+            ("LeftShift", KeyCode::Modifier(ModifierKeyCode::LeftShift)),
+            ("RightAlt", KeyCode::Modifier(ModifierKeyCode::RightAlt)),
+            ("IsoLevel5Shift", KeyCode::Modifier(ModifierKeyCode::IsoLevel5Shift)),
+        ] {
+            let desered_t: T = toml::from_str(&format!("kb = \"{config}\"\n")).unwrap();
+            assert_eq!(desered_t.kb.code, code, "parse {config}");
+            assert_eq!(
+                toml::to_string(&desered_t).unwrap(),
+                format!("kb = \"{config}\"\n")
+            );
+            assert_eq!(format!("{:?}", desered_t.kb), format!("\"{config}\""));
+            assert_eq!(desered_t.kb.display(&DisplayFormat::Full), config);
+        }
+
+        let desered_t: T = toml::from_str("kb = \"Control+RightShift\"\n").unwrap();
+        assert_eq!(format!("{}", desered_t.kb), "^RightShift");
+
+        assert!(toml::from_str::<T>("kb = \"F256\"\n").is_err());
+
+        // every key is written back in a form read as the same key
+        let chars = [' ', '+', '-', ',', ':', '\u{a0}', '\t', '\u{3000}'].map(KeyCode::Char);
+        let fn_keys = [KeyCode::F(0), KeyCode::F(255)];
+        for code in NAMED_KEYS.iter().map(|(_, code)| *code).chain(chars).chain(fn_keys) {
+            let kb = KeyBinding {
+                code,
+                modifiers: KeyModifiers::CONTROL,
+                kind: KeyEventKind::Press,
+            };
+            let serialized = toml::to_string(&T { kb: kb.clone() }).unwrap();
+            let desered_t: T = toml::from_str(&serialized).unwrap();
+            assert_eq!(desered_t.kb, kb, "round trip {serialized}");
+        }
+        assert_eq!(keycode_to_string(&KeyCode::Char('\u{a0}')), "U00A0");
+    }
+
+    #[test]
+    fn kind_keybinding_config() {
+        let desered: U =
+            toml::from_str("kbs = [\"Release:Space\", \"Repeat:Control+j\", \"Press:k\", \":\", \"Shift+:\"]\n")
+                .unwrap();
+        let kinds: Vec<_> = desered
+            .kbs
+            .sequences()
+            .iter()
+            .map(|seq| seq.single().unwrap().kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                KeyEventKind::Release,
+                KeyEventKind::Repeat,
+                KeyEventKind::Press,
+                KeyEventKind::Press,
+                KeyEventKind::Press
+            ]
+        );
+        assert_eq!(
+            toml::to_string(&desered).unwrap(),
+            "kbs = [\"Release:Space\", \"Repeat:Control+j\", \"k\", \":\", \"Shift+:\"]\n"
+        );
+        assert_eq!(
+            desered.kbs.display(&DisplayFormat::Abbreviation),
+            "Release:Space | Repeat:Ctrl+j | k | : | Shift+:"
+        );
+
+        let press = KeyEvent::new(KeyCode::Char('k'), KeyModifiers::NONE);
+        let release =
+            KeyEvent::new_with_kind(KeyCode::Char('k'), KeyModifiers::NONE, KeyEventKind::Release);
+        assert!(desered.kbs.match_any(&press));
+        assert!(!desered.kbs.match_any(&release));
+
+        let space_release =
+            KeyEvent::new_with_kind(KeyCode::Char(' '), KeyModifiers::NONE, KeyEventKind::Release);
+        let space_press = KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE);
+        assert!(desered.kbs.match_any(&space_release));
+        assert!(!desered.kbs.match_any(&space_press));
+
+        let j_repeat =
+            KeyEvent::new_with_kind(KeyCode::Char('j'), KeyModifiers::CONTROL, KeyEventKind::Repeat);
+        assert!(desered.kbs.match_any(&j_repeat));
+    }
+
+    #[test]
+    #[cfg(feature = "shift_normalize")]
+    fn match_shift_normalized_keybinding() {
+        let upper = KeyEvent::new(KeyCode::Char('A'), KeyModifiers::NONE);
+        let shift_upper = KeyEvent::new(KeyCode::Char('A'), KeyModifiers::SHIFT);
+        let shift_lower = KeyEvent::new(KeyCode::Char('a'), KeyModifiers::SHIFT);
+        let lower = KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE);
+        for config in ["A", "Shift+a", "Shift+A"] {
+            let desered_t: T = toml::from_str(&format!("kb = \"{config}\"\n")).unwrap();
+            assert!(desered_t.kb.matches(&upper), "{config} matches 'A'");
+            assert!(desered_t.kb.matches(&shift_upper), "{config} matches Shift+'A'");
+            assert!(desered_t.kb.matches(&shift_lower), "{config} matches Shift+'a'");
+            assert!(!desered_t.kb.matches(&lower), "{config} does not match 'a'");
+        }
+
+        let desered_t: T = toml::from_str("kb = \"?\"\n").unwrap();
+        assert!(desered_t.kb.matches(&KeyEvent::new(KeyCode::Char('?'), KeyModifiers::SHIFT)));
+
+        // the chars which do not show the Shift keep it
+        let desered_t: T = toml::from_str("kb = \"Shift+1\"\n").unwrap();
+        assert!(!desered_t.kb.matches(&KeyEvent::new(KeyCode::Char('1'), KeyModifiers::NONE)));
+        assert!(desered_t.kb.matches(&KeyEvent::new(KeyCode::Char('1'), KeyModifiers::SHIFT)));
+        let desered_t: T = toml::from_str("kb = \"1\"\n").unwrap();
+        assert!(!desered_t.kb.matches(&KeyEvent::new(KeyCode::Char('1'), KeyModifiers::SHIFT)));
+
+        let desered_t: T = toml::from_str("kb = \"Control+Shift+p\"\n").unwrap();
+        assert!(desered_t.kb.matches(&KeyEvent::new(
+            KeyCode::Char('P'),
+            KeyModifiers::CONTROL | KeyModifiers::SHIFT
+        )));
+        assert!(desered_t.kb.matches(&KeyEvent::new(KeyCode::Char('P'), KeyModifiers::CONTROL)));
+    }
+
+    #[test]
+    fn parse_keybinding_errors() {
+        assert_eq!(
+            KeySequence::parse("g Contrl+PgUp"),
+            Err(ParseKeyBindingError::UnknownModifier {
+                name: "Contrl".to_string(),
+                span: 2..8,
+                suggestion: Some("Control"),
+            })
+        );
+        let e = KeySequence::parse("Control+x PgUp").unwrap_err();
+        assert_eq!(e.span(), 10..14);
+        assert_eq!(e.suggestion(), Some("PageUp"));
+        assert_eq!(
+            e.to_string(),
+            "unknown key `PgUp` at 10..14, please use a char or a key name, did you mean `PageUp`?"
+        );
+        assert_eq!(
+            KeyBinding::parse("Control+"),
+            Err(ParseKeyBindingError::EmptyKey { span: 8..8 })
+        );
+        assert_eq!(
+            KeySequence::parse(""),
+            Err(ParseKeyBindingError::EmptyKey { span: 0..0 })
+        );
+        assert_eq!(
+            KeyBinding::parse("NotARealKey").unwrap_err().suggestion(),
+            None
+        );
+
+        let e = toml::from_str::<T>("kb = \"Alt+Entr\"\n").map(|_| ()).unwrap_err();
+        assert!(e.to_string().contains("did you mean `Enter`?"), "{e}");
+    }
+
+    #[test]
+    fn keybindings_from_str() {
+        let kb: KeyBinding = "Control+Shift+p".parse().unwrap();
+        assert_eq!(kb, KeyBinding::new(KeyCode::Char('p'), KeyModifiers::CONTROL | KeyModifiers::SHIFT));
+        let event = KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL | KeyModifiers::SHIFT);
+        assert_eq!(KeyBinding::from(event), kb);
+
+        let mut kbs: KeyBindings = r#"["Control+c", "g g", "\"", "Plus",]"#.parse().unwrap();
+        assert_eq!(format!("{:?}", kbs), r#"["Control+c", "g g", "\"", "Plus"]"#);
+        assert_eq!(format!("{:?}", kbs).parse::<KeyBindings>().unwrap(), kbs);
+        assert!(kbs.contains(KeyBinding::new(KeyCode::Char('c'), KeyModifiers::CONTROL)));
+        assert!(kbs.contains("g g".parse::<KeySequence>().unwrap()));
+
+        kbs.push(event);
+        kbs.push(event);
+        assert_eq!(kbs.iter().count(), 5);
+        assert!(kbs.match_any(&event));
+        assert!(kbs.remove(event));
+        assert!(!kbs.remove(event));
+        assert!(!kbs.match_any(&event));
+
+        let kbs: KeyBindings = "Control+c".parse().unwrap();
+        assert_eq!(kbs, KeyBindings::from(vec![KeySequence::new(vec![KeyBinding::new(KeyCode::Char('c'), KeyModifiers::CONTROL)])]));
+        assert!(KeyBindings::new().iter().next().is_none());
+
+        assert_eq!(
+            r#"["q", "Contrl+c"]"#.parse::<KeyBindings>().unwrap_err().span(),
+            7..13
+        );
+        assert_eq!(
+            r#"["q" "c"]"#.parse::<KeyBindings>(),
+            Err(ParseKeyBindingError::MalformedList { span: 5..5 })
+        );
+        assert_eq!(
+            r#"["q""#.parse::<KeyBindings>(),
+            Err(ParseKeyBindingError::MalformedList { span: 4..4 })
+        );
+        assert_eq!(
+            r#"["q]"#.parse::<KeyBindings>(),
+            Err(ParseKeyBindingError::MalformedList { span: 4..4 })
+        );
+    }
+
+    #[test]
+    fn keybindings_from_str_unescapes() {
+        let q = KeySequence::new(vec![KeyBinding::new(KeyCode::Char('q'), KeyModifiers::NONE)]);
+        let kbs: KeyBindings = r#"["\u0071", 'q', '\']"#.parse().unwrap();
+        assert_eq!(format!("{:?}", kbs), r#"["q", "q", "\\"]"#);
+        assert!(kbs.contains(q));
+        assert_eq!(format!("{:?}", kbs).parse::<KeyBindings>().unwrap(), kbs);
+
+        // the escaped whitespace is not a key, the span covers the whole string
+        assert_eq!(
+            r#"["\t"]"#.parse::<KeyBindings>(),
+            Err(ParseKeyBindingError::EmptyKey { span: 1..5 })
+        );
+        assert!(r#"["\u00A0"]"#.parse::<KeyBindings>().is_err());
+        assert_eq!(r#"['q', 'Contrl+c']"#.parse::<KeyBindings>().unwrap_err().span(), 7..13);
+    }
+
+    #[test]
+    fn keybindings_from_str_with_condition() {
+        let kbs: KeyBindings = r#"["q", { key = "Enter", when = "listFocused" }]"#.parse().unwrap();
+        assert_eq!(format!("{:?}", kbs), r#"["q", { key = "Enter", when = "listFocused" }]"#);
+        assert_eq!(format!("{:?}", kbs).parse::<KeyBindings>().unwrap(), kbs);
+
+        assert_eq!(
+            r#"[{ key = "Entr" }]"#.parse::<KeyBindings>().unwrap_err().span(),
+            10..14
+        );
+        assert!(matches!(
+            r#"[{ key = "Enter", when = "a &&" }]"#.parse::<KeyBindings>(),
+            Err(ParseKeyBindingError::MalformedCondition { .. })
+        ));
+        assert_eq!(
+            r#"[{ key = "Enter", if = "a" }]"#.parse::<KeyBindings>(),
+            Err(ParseKeyBindingError::MalformedList { span: 18..20 })
+        );
+        assert_eq!(
+            r#"[{ when = "a" }]"#.parse::<KeyBindings>(),
+            Err(ParseKeyBindingError::MalformedList { span: 1..15 })
+        );
+    }
+
     /// Return keybind config with modifiers, keybind without modifiers, only modifiers
     fn keybinding_configs() -> (T, T, T, T, T) {
         (
@@ -602,30 +1444,35 @@ mod tests {
                 kb: KeyBinding {
                     code: KeyCode::Char('c'),
                     modifiers: KeyModifiers::CONTROL,
+                    kind: KeyEventKind::Press,
                 },
             },
             T {
                 kb: KeyBinding {
                     code: KeyCode::Char('c'),
                     modifiers: KeyModifiers::ALT,
+                    kind: KeyEventKind::Press,
                 },
             },
             T {
                 kb: KeyBinding {
                     code: KeyCode::Char('Q'),
                     modifiers: KeyModifiers::NONE,
+                    kind: KeyEventKind::Press,
                 },
             },
             T {
                 kb: KeyBinding {
                     code: KeyCode::Null,
                     modifiers: KeyModifiers::ALT,
+                    kind: KeyEventKind::Press,
                 },
             },
             T {
                 kb: KeyBinding {
                     code: KeyCode::Esc,
                     modifiers: KeyModifiers::NONE,
+                    kind: KeyEventKind::Press,
                 },
             },
         )
@@ -638,11 +1485,15 @@ mod tests {
                 KeyBinding {
                     code: KeyCode::Char('c'),
                     modifiers: KeyModifiers::CONTROL,
-                },
+                    kind: KeyEventKind::Press,
+                }
+                .into(),
                 KeyBinding {
                     code: KeyCode::Char('Q'),
                     modifiers: KeyModifiers::NONE,
-                },
+                    kind: KeyEventKind::Press,
+                }
+                .into(),
             ]),
         }
     }
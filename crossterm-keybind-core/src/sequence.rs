@@ -0,0 +1,461 @@
+#[cfg(feature = "crossterm_0_28_1")]
+use crossterm_0_28_1::event::{KeyEvent, KeyEventKind};
+#[cfg(feature = "crossterm_0_29_0")]
+use crossterm_0_29_0::event::{KeyEvent, KeyEventKind};
+
+use crate::{KeyBindings, KeyContext, KeySequence};
+use std::time::{Duration, Instant};
+
+/// The result of feeding a key event into [`KeySequenceMatcher`]
+#[derive(Debug, PartialEq)]
+pub enum SequenceMatch<T> {
+    /// The key strokes complete the key sequences of these actions
+    Matched(Vec<T>),
+
+    /// The key strokes are a prefix of some key sequences, more key strokes are expected
+    Pending,
+
+    /// The key strokes do not match any key sequence
+    Discarded,
+
+    /// The pending key strokes complete the key sequences of these actions, but the key stroke
+    /// breaks the longer ones, e.g. `"g"` followed by `"q"` when waiting for `"g g"`, so the key
+    /// stroke is matched from the beginning with the boxed result
+    Interrupted(Vec<T>, Box<SequenceMatch<T>>),
+}
+
+impl<T> SequenceMatch<T> {
+    /// The actions matched by the key strokes, in the order they are completed
+    pub fn into_matched(self) -> Vec<T> {
+        match self {
+            SequenceMatch::Matched(matched) => matched,
+            SequenceMatch::Pending | SequenceMatch::Discarded => Vec::new(),
+            SequenceMatch::Interrupted(mut matched, next) => {
+                matched.extend(next.into_matched());
+                matched
+            }
+        }
+    }
+}
+
+/// A stateful matcher which takes key events one by one and resolves multi-key sequences,
+/// e.g. `"g g"` or `"Control+x Control+s"`.
+///
+/// When a key sequence is also the prefix of a longer one, the matcher keeps waiting for the
+/// longer one and reports [`SequenceMatch::Pending`].
+/// If the following key stroke does not continue any key sequence, the pending key strokes resolve
+/// to the key sequences they complete by themselves, reported as [`SequenceMatch::Interrupted`],
+/// or are discarded, and the key stroke is matched from the beginning.
+/// Release and repeat events not bound at the current position are ignored, so they do not break
+/// the pending key strokes.
+///
+/// The matcher does not spawn any thread for the timeout of the pending key strokes, the time is
+/// provided by the caller with [`KeySequenceMatcher::feed_at`] and
+/// [`KeySequenceMatcher::poll_timeout`] from its own event loop.
+///
+/// The conditions of key sequences are evaluated in the empty context, unless the context is
+/// provided with the `*_with` methods.
+pub struct KeySequenceMatcher<T> {
+    actions: Vec<(T, KeyBindings)>,
+    pending: Vec<KeyEvent>,
+    timeout: Option<Duration>,
+    last_stroke_at: Option<Instant>,
+    legacy: bool,
+}
+
+impl<T> Default for KeySequenceMatcher<T> {
+    fn default() -> Self {
+        Self {
+            actions: Vec::new(),
+            pending: Vec::new(),
+            timeout: None,
+            last_stroke_at: None,
+            legacy: false,
+        }
+    }
+}
+
+impl<T> KeySequenceMatcher<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the timeout of the pending key strokes, `None` for waiting forever
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// The timeout of the pending key strokes
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Set the terminal is in legacy mode, such that the key strokes legacy terminals can not
+    /// distinguish are folded into the same key, see [`KeyBinding::matches_with_legacy`]
+    ///
+    /// [`KeyBinding::matches_with_legacy`]: crate::KeyBinding::matches_with_legacy
+    pub fn set_legacy_mode(&mut self, enabled: bool) {
+        self.legacy = enabled;
+    }
+
+    /// The instant the pending key strokes time out, the caller can use it to decide how long to
+    /// wait for the next event
+    pub fn deadline(&self) -> Option<Instant> {
+        if self.pending.is_empty() {
+            return None;
+        }
+        Some(self.last_stroke_at? + self.timeout?)
+    }
+
+    /// Add an action triggered by any of the key bindings
+    pub fn add(&mut self, action: T, key_bindings: KeyBindings) {
+        self.actions.push((action, key_bindings));
+    }
+
+    /// The key events waiting for the following key strokes
+    pub fn pending(&self) -> &[KeyEvent] {
+        &self.pending
+    }
+
+    /// Drop the pending key strokes
+    pub fn reset(&mut self) {
+        self.pending.clear();
+        self.last_stroke_at = None;
+    }
+
+    /// Check any key sequence starting with the pending key strokes continues with the key event
+    fn continues_with(&self, key_event: &KeyEvent, ctx: &dyn KeyContext) -> bool {
+        self.candidates(ctx).any(|(_, seq)| {
+            seq.key_bindings()
+                .get(self.pending.len())
+                .is_some_and(|kb| kb.matches_with_legacy(key_event, self.legacy))
+        })
+    }
+
+    /// The key sequences starting with the pending key strokes, whose conditions hold
+    fn candidates(&self, ctx: &dyn KeyContext) -> impl Iterator<Item = (&T, &KeySequence)> {
+        self.actions
+            .iter()
+            .flat_map(|(action, kbs)| kbs.sequences().iter().map(move |seq| (action, seq)))
+            .filter(move |(_, seq)| {
+                let strokes = seq.key_bindings();
+                seq.holds(ctx)
+                    && strokes.len() >= self.pending.len()
+                    && strokes
+                        .iter()
+                        .zip(self.pending.iter())
+                        .all(|(kb, key_event)| kb.matches_with_legacy(key_event, self.legacy))
+            })
+    }
+}
+
+impl<T: Clone> KeySequenceMatcher<T> {
+    /// Feed a key event and get the matching result of all the key strokes so far
+    pub fn feed(&mut self, key_event: &KeyEvent) -> SequenceMatch<T> {
+        self.feed_with(key_event, &())
+    }
+
+    /// Feed a key event, the conditions of key sequences are evaluated in the context
+    pub fn feed_with(&mut self, key_event: &KeyEvent, ctx: &dyn KeyContext) -> SequenceMatch<T> {
+        if key_event.kind != KeyEventKind::Press && !self.continues_with(key_event, ctx) {
+            return if self.pending.is_empty() {
+                SequenceMatch::Discarded
+            } else {
+                SequenceMatch::Pending
+            };
+        }
+        self.pending.push(*key_event);
+
+        let mut matched = Vec::new();
+        let mut has_longer = false;
+        for (action, seq) in self.candidates(ctx) {
+            if seq.key_bindings().len() == self.pending.len() {
+                matched.push(action.clone());
+            } else {
+                has_longer = true;
+            }
+        }
+
+        if has_longer {
+            SequenceMatch::Pending
+        } else if !matched.is_empty() {
+            self.reset();
+            SequenceMatch::Matched(matched)
+        } else if self.pending.len() > 1 {
+            self.pending.pop();
+            // the pending key strokes without the key stroke may complete shorter key sequences
+            let completed: Vec<T> = self
+                .candidates(ctx)
+                .filter(|(_, seq)| seq.key_bindings().len() == self.pending.len())
+                .map(|(action, _)| action.clone())
+                .collect();
+            self.pending.clear();
+            let next = self.feed_with(key_event, ctx);
+            if completed.is_empty() {
+                next
+            } else {
+                SequenceMatch::Interrupted(completed, Box::new(next))
+            }
+        } else {
+            self.reset();
+            SequenceMatch::Discarded
+        }
+    }
+
+    /// Feed a key event received at `now`.
+    ///
+    /// Pending key strokes already timed out at `now` are resolved as
+    /// [`KeySequenceMatcher::poll_timeout`] does before the key event is matched from the
+    /// beginning, the actions they complete are reported with [`SequenceMatch::Interrupted`].
+    pub fn feed_at(&mut self, key_event: &KeyEvent, now: Instant) -> SequenceMatch<T> {
+        self.feed_at_with(key_event, now, &())
+    }
+
+    /// Feed a key event received at `now`, the conditions of key sequences are evaluated in the
+    /// context
+    pub fn feed_at_with(
+        &mut self,
+        key_event: &KeyEvent,
+        now: Instant,
+        ctx: &dyn KeyContext,
+    ) -> SequenceMatch<T> {
+        let timed_out = self.poll_timeout_with(now, ctx);
+        // an ignored release or repeat event does not extend the timeout
+        let ignored = key_event.kind != KeyEventKind::Press && !self.continues_with(key_event, ctx);
+        let result = self.feed_with(key_event, ctx);
+        if !ignored && !self.pending.is_empty() {
+            self.last_stroke_at = Some(now);
+        }
+        match timed_out {
+            Some(SequenceMatch::Matched(completed)) => {
+                SequenceMatch::Interrupted(completed, Box::new(result))
+            }
+            _ => result,
+        }
+    }
+
+    /// Resolve the pending key strokes if they time out at `now`.
+    ///
+    /// If the pending key strokes are a complete key sequence by themselves, e.g. `"g"` when
+    /// waiting for `"g g"`, the shorter key sequence is matched, else they are discarded.
+    /// Return `None` if there is nothing to resolve.
+    pub fn poll_timeout(&mut self, now: Instant) -> Option<SequenceMatch<T>> {
+        self.poll_timeout_with(now, &())
+    }
+
+    /// Resolve the pending key strokes if they time out at `now`, the conditions of key sequences
+    /// are evaluated in the context
+    pub fn poll_timeout_with(
+        &mut self,
+        now: Instant,
+        ctx: &dyn KeyContext,
+    ) -> Option<SequenceMatch<T>> {
+        if self.deadline().is_none_or(|deadline| now < deadline) {
+            return None;
+        }
+        let matched: Vec<T> = self
+            .candidates(ctx)
+            .filter(|(_, seq)| seq.key_bindings().len() == self.pending.len())
+            .map(|(action, _)| action.clone())
+            .collect();
+        self.reset();
+        if matched.is_empty() {
+            Some(SequenceMatch::Discarded)
+        } else {
+            Some(SequenceMatch::Matched(matched))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_key_sequence;
+
+    #[cfg(feature = "crossterm_0_28_1")]
+    use crossterm_0_28_1::event::{KeyCode, KeyModifiers};
+    #[cfg(feature = "crossterm_0_29_0")]
+    use crossterm_0_29_0::event::{KeyCode, KeyModifiers};
+
+    fn key_bindings(sequences: &[&str]) -> KeyBindings {
+        KeyBindings(
+            sequences
+                .iter()
+                .map(|s| parse_key_sequence(s).unwrap())
+                .collect(),
+        )
+    }
+
+    fn matcher() -> KeySequenceMatcher<&'static str> {
+        let mut matcher = KeySequenceMatcher::new();
+        matcher.add("top", key_bindings(&["g g"]));
+        matcher.add("save", key_bindings(&["Control+x Control+s"]));
+        matcher.add("quit", key_bindings(&["q"]));
+        matcher
+    }
+
+    #[test]
+    fn match_sequences() {
+        let mut matcher = matcher();
+        let g = KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE);
+        assert_eq!(matcher.feed(&g), SequenceMatch::Pending);
+        assert_eq!(matcher.feed(&g), SequenceMatch::Matched(vec!["top"]));
+        assert!(matcher.pending().is_empty());
+
+        let ctrl_x = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::CONTROL);
+        let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+        assert_eq!(matcher.feed(&ctrl_x), SequenceMatch::Pending);
+        assert_eq!(matcher.feed(&ctrl_s), SequenceMatch::Matched(vec!["save"]));
+
+        let q = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE);
+        assert_eq!(matcher.feed(&q), SequenceMatch::Matched(vec!["quit"]));
+    }
+
+    #[test]
+    fn discard_broken_sequences() {
+        let mut matcher = matcher();
+        let g = KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE);
+        let x = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE);
+        let q = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE);
+        assert_eq!(matcher.feed(&x), SequenceMatch::Discarded);
+        assert_eq!(matcher.feed(&g), SequenceMatch::Pending);
+        assert_eq!(matcher.feed(&x), SequenceMatch::Discarded);
+        assert!(matcher.pending().is_empty());
+
+        // The key stroke breaking a sequence is matched from the beginning
+        assert_eq!(matcher.feed(&g), SequenceMatch::Pending);
+        assert_eq!(matcher.feed(&q), SequenceMatch::Matched(vec!["quit"]));
+    }
+
+    #[test]
+    fn resolve_interrupted_sequences() {
+        let mut matcher = matcher();
+        matcher.add("go", key_bindings(&["g"]));
+        let g = KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE);
+        let x = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE);
+        let q = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE);
+
+        // The pending `g` is complete by itself, it is matched before the breaking key stroke
+        assert_eq!(matcher.feed(&g), SequenceMatch::Pending);
+        let result = matcher.feed(&q);
+        assert_eq!(
+            result,
+            SequenceMatch::Interrupted(vec!["go"], Box::new(SequenceMatch::Matched(vec!["quit"])))
+        );
+        assert_eq!(result.into_matched(), vec!["go", "quit"]);
+        assert!(matcher.pending().is_empty());
+
+        assert_eq!(matcher.feed(&g), SequenceMatch::Pending);
+        assert_eq!(
+            matcher.feed(&x),
+            SequenceMatch::Interrupted(vec!["go"], Box::new(SequenceMatch::Discarded))
+        );
+
+        // The breaking key stroke can start a new sequence
+        assert_eq!(matcher.feed(&g), SequenceMatch::Pending);
+        let ctrl_x = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::CONTROL);
+        assert_eq!(
+            matcher.feed(&ctrl_x),
+            SequenceMatch::Interrupted(vec!["go"], Box::new(SequenceMatch::Pending))
+        );
+        assert_eq!(matcher.pending(), &[ctrl_x]);
+    }
+
+    #[test]
+    fn ignore_unbound_release_events() {
+        let mut matcher = matcher();
+        matcher.add("hold", key_bindings(&["Release:h"]));
+        let g = KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE);
+        let g_release = KeyEvent::new_with_kind(
+            KeyCode::Char('g'),
+            KeyModifiers::NONE,
+            KeyEventKind::Release,
+        );
+        assert_eq!(matcher.feed(&g_release), SequenceMatch::Discarded);
+        assert_eq!(matcher.feed(&g), SequenceMatch::Pending);
+        assert_eq!(matcher.feed(&g_release), SequenceMatch::Pending);
+        assert_eq!(matcher.feed(&g), SequenceMatch::Matched(vec!["top"]));
+
+        let h_release = KeyEvent::new_with_kind(
+            KeyCode::Char('h'),
+            KeyModifiers::NONE,
+            KeyEventKind::Release,
+        );
+        assert_eq!(
+            matcher.feed(&h_release),
+            SequenceMatch::Matched(vec!["hold"])
+        );
+    }
+
+    #[test]
+    fn resolve_timeout_sequences() {
+        let mut matcher = matcher();
+        matcher.add("go", key_bindings(&["g"]));
+        matcher.set_timeout(Some(Duration::from_millis(500)));
+
+        let start = Instant::now();
+        let g = KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE);
+        assert_eq!(matcher.poll_timeout(start), None);
+        assert_eq!(matcher.feed_at(&g, start), SequenceMatch::Pending);
+        assert_eq!(matcher.deadline(), Some(start + Duration::from_millis(500)));
+        assert_eq!(
+            matcher.poll_timeout(start + Duration::from_millis(100)),
+            None
+        );
+        assert_eq!(
+            matcher.feed_at(&g, start + Duration::from_millis(200)),
+            SequenceMatch::Matched(vec!["top"])
+        );
+
+        // A lone `g` resolves to the shorter key sequence
+        assert_eq!(matcher.feed_at(&g, start), SequenceMatch::Pending);
+        assert_eq!(
+            matcher.poll_timeout(start + Duration::from_millis(500)),
+            Some(SequenceMatch::Matched(vec!["go"]))
+        );
+        assert_eq!(matcher.deadline(), None);
+
+        // A lone `Control+x` has nothing to resolve to
+        let ctrl_x = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::CONTROL);
+        assert_eq!(matcher.feed_at(&ctrl_x, start), SequenceMatch::Pending);
+        assert_eq!(
+            matcher.poll_timeout(start + Duration::from_secs(1)),
+            Some(SequenceMatch::Discarded)
+        );
+
+        // A key event after the timeout resolves the pending key strokes and starts a new sequence
+        assert_eq!(matcher.feed_at(&g, start), SequenceMatch::Pending);
+        assert_eq!(
+            matcher.feed_at(&g, start + Duration::from_secs(1)),
+            SequenceMatch::Interrupted(vec!["go"], Box::new(SequenceMatch::Pending))
+        );
+        assert_eq!(matcher.deadline(), Some(start + Duration::from_millis(1500)));
+        matcher.reset();
+
+        assert_eq!(matcher.feed_at(&ctrl_x, start), SequenceMatch::Pending);
+        let q = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE);
+        assert_eq!(
+            matcher.feed_at(&q, start + Duration::from_secs(1)),
+            SequenceMatch::Matched(vec!["quit"])
+        );
+    }
+
+    #[test]
+    fn release_events_keep_timeout() {
+        let mut matcher = matcher();
+        matcher.set_timeout(Some(Duration::from_millis(500)));
+        let start = Instant::now();
+        let g = KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE);
+        let g_release = KeyEvent::new_with_kind(
+            KeyCode::Char('g'),
+            KeyModifiers::NONE,
+            KeyEventKind::Release,
+        );
+        assert_eq!(matcher.feed_at(&g, start), SequenceMatch::Pending);
+        assert_eq!(
+            matcher.feed_at(&g_release, start + Duration::from_millis(300)),
+            SequenceMatch::Pending
+        );
+        assert_eq!(matcher.deadline(), Some(start + Duration::from_millis(500)));
+    }
+}
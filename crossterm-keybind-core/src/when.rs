@@ -0,0 +1,230 @@
+use crate::ParseKeyBindingError;
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+
+/// The context of the application to evaluate the [`When`] conditions of key bindings, e.g. the
+/// focus of widgets
+///
+/// The empty context `()` has no flag set, which is used when the key bindings are matched
+/// without a context.
+pub trait KeyContext {
+    /// The named flag is set or not
+    fn flag(&self, name: &str) -> bool;
+}
+
+impl KeyContext for () {
+    fn flag(&self, _name: &str) -> bool {
+        false
+    }
+}
+
+impl<F: Fn(&str) -> bool> KeyContext for F {
+    fn flag(&self, name: &str) -> bool {
+        self(name)
+    }
+}
+
+impl KeyContext for HashSet<String> {
+    fn flag(&self, name: &str) -> bool {
+        self.contains(name)
+    }
+}
+
+impl KeyContext for HashSet<&str> {
+    fn flag(&self, name: &str) -> bool {
+        self.contains(name)
+    }
+}
+
+impl KeyContext for BTreeSet<String> {
+    fn flag(&self, name: &str) -> bool {
+        self.contains(name)
+    }
+}
+
+impl<const N: usize> KeyContext for [&str; N] {
+    fn flag(&self, name: &str) -> bool {
+        self.contains(&name)
+    }
+}
+
+impl KeyContext for &[&str] {
+    fn flag(&self, name: &str) -> bool {
+        self.contains(&name)
+    }
+}
+
+/// A condition of a key binding over named flags of [`KeyContext`], e.g. `"listFocused && !readOnly"`
+///
+/// `!` binds tighter than `&&`, which binds tighter than `||`, and parentheses group conditions.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum When {
+    Flag(String),
+    Not(Box<When>),
+    And(Box<When>, Box<When>),
+    Or(Box<When>, Box<When>),
+}
+
+impl When {
+    /// Parse a condition, e.g. `"listFocused && !readOnly"`
+    pub fn parse(s: &str) -> Result<When, ParseKeyBindingError> {
+        let mut parser = Parser { s, pos: 0 };
+        let when = parser.or()?;
+        parser.skip_whitespace();
+        if parser.pos < s.len() {
+            return Err(parser.error());
+        }
+        Ok(when)
+    }
+
+    /// Evaluate the condition in the context
+    pub fn holds(&self, ctx: &dyn KeyContext) -> bool {
+        match self {
+            When::Flag(name) => ctx.flag(name),
+            When::Not(w) => !w.holds(ctx),
+            When::And(a, b) => a.holds(ctx) && b.holds(ctx),
+            When::Or(a, b) => a.holds(ctx) || b.holds(ctx),
+        }
+    }
+}
+
+/// A recursive descent parser of conditions
+struct Parser<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        let rest = &self.s[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        if self.s[self.pos..].starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// The error at the current position, spanning the rest of the token
+    fn error(&self) -> ParseKeyBindingError {
+        let rest = &self.s[self.pos..];
+        let len = rest
+            .find(char::is_whitespace)
+            .unwrap_or(rest.len())
+            .max(rest.chars().next().map_or(0, char::len_utf8));
+        ParseKeyBindingError::MalformedCondition {
+            span: self.pos..self.pos + len,
+        }
+    }
+
+    fn or(&mut self) -> Result<When, ParseKeyBindingError> {
+        let mut when = self.and()?;
+        while self.eat("||") {
+            when = When::Or(Box::new(when), Box::new(self.and()?));
+        }
+        Ok(when)
+    }
+
+    fn and(&mut self) -> Result<When, ParseKeyBindingError> {
+        let mut when = self.not()?;
+        while self.eat("&&") {
+            when = When::And(Box::new(when), Box::new(self.not()?));
+        }
+        Ok(when)
+    }
+
+    fn not(&mut self) -> Result<When, ParseKeyBindingError> {
+        if self.eat("!") {
+            return Ok(When::Not(Box::new(self.not()?)));
+        }
+        if self.eat("(") {
+            let when = self.or()?;
+            if !self.eat(")") {
+                return Err(self.error());
+            }
+            return Ok(when);
+        }
+        self.skip_whitespace();
+        let rest = &self.s[self.pos..];
+        let len = rest
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '.' | '-' | ':')))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error());
+        }
+        self.pos += len;
+        Ok(When::Flag(rest[..len].to_string()))
+    }
+}
+
+/// Write an operand of `&&`, which is grouped if it is `||`
+fn fmt_and_operand(w: &When, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if let When::Or(..) = w {
+        write!(f, "({w})")
+    } else {
+        write!(f, "{w}")
+    }
+}
+
+impl fmt::Display for When {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            When::Flag(name) => write!(f, "{name}"),
+            When::Not(w) => match **w {
+                When::And(..) | When::Or(..) => write!(f, "!({w})"),
+                _ => write!(f, "!{w}"),
+            },
+            When::And(a, b) => {
+                fmt_and_operand(a, f)?;
+                write!(f, " && ")?;
+                fmt_and_operand(b, f)
+            }
+            When::Or(a, b) => write!(f, "{a} || {b}"),
+        }
+    }
+}
+
+impl fmt::Debug for When {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\"{self}\"")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_evaluate_when() {
+        let when = When::parse("listFocused && !readOnly").unwrap();
+        assert_eq!(when.to_string(), "listFocused && !readOnly");
+        assert!(when.holds(&["listFocused"]));
+        assert!(!when.holds(&["listFocused", "readOnly"]));
+        assert!(!when.holds(&()));
+
+        let when = When::parse("!(a || b) && (c || d) || e").unwrap();
+        assert_eq!(when.to_string(), "!(a || b) && (c || d) || e");
+        assert!(when.holds(&|name: &str| name == "d"));
+        assert!(!when.holds(&|name: &str| name == "a" || name == "d"));
+        assert!(when.holds(&HashSet::from(["e"])));
+        assert_eq!(When::parse(" editor.focus ").unwrap(), When::Flag("editor.focus".into()));
+
+        assert_eq!(
+            When::parse("a && && b"),
+            Err(ParseKeyBindingError::MalformedCondition { span: 5..7 })
+        );
+        assert_eq!(
+            When::parse("(a || b"),
+            Err(ParseKeyBindingError::MalformedCondition { span: 7..7 })
+        );
+        assert_eq!(
+            When::parse("a b"),
+            Err(ParseKeyBindingError::MalformedCondition { span: 2..3 })
+        );
+    }
+}
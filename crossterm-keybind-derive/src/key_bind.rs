@@ -2,10 +2,263 @@ use proc_macro::{Span, TokenStream};
 use quote::{quote, ToTokens};
 use syn::{Attribute, DeriveInput, Error, Fields, Ident, Meta, Result, Variant};
 
+/// A value of a variant with data, e.g. `SwitchTab(1)`
+struct Value {
+    ident: Ident,
+    args: Vec<syn::Lit>,
+}
+
+impl syn::parse::Parse for Value {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let ident: Ident = input.parse()?;
+        let content;
+        syn::parenthesized!(content in input);
+        let args = content.parse_terminated(syn::Lit::parse, syn::Token![,])?;
+        Ok(Value {
+            ident,
+            args: args.into_iter().collect(),
+        })
+    }
+}
+
+impl Value {
+    /// The tokens of the arguments, which identify the value
+    fn args_string(&self) -> String {
+        self.args
+            .iter()
+            .map(|arg| arg.to_token_stream().to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// The name of the value in the config, e.g. `switch_tab_1` for `SwitchTab(1)`
+    fn field(&self, lower: &Ident) -> Result<Ident> {
+        let mut name = lower.to_string();
+        for arg in self.args.iter() {
+            let arg = match arg {
+                syn::Lit::Int(i) => i.base10_digits().to_string(),
+                syn::Lit::Str(s) => s.value(),
+                syn::Lit::Char(c) => c.value().to_string(),
+                syn::Lit::Bool(b) => b.value.to_string(),
+                _ => {
+                    return Err(Error::new(
+                        arg.span(),
+                        "The arguments of a value should be integers, chars, strings or bools",
+                    ))
+                }
+            };
+            name.push('_');
+            name.extend(arg.chars().map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '_'
+                }
+            }));
+        }
+        Ok(Ident::new(&name, Span::call_site().into()))
+    }
+}
+
+/// The key bindings of an attribute
+enum Bindings {
+    /// A list of key bindings, e.g. `["Control+c", "q"]`
+    List(String),
+    /// The key bindings of the values of a variant with data, e.g. `"Alt+1" => SwitchTab(1)`
+    Values(Vec<(syn::LitStr, Value)>),
+}
+
+/// The kind of a variant, which decides how it is bound to keys
+#[derive(PartialEq)]
+enum Kind {
+    /// A variant without data
+    Unit,
+    /// A variant with a single field bound with the `<digit>` placeholder, e.g. `"Alt+<digit>"`
+    Digit,
+    /// A variant with data bound to values, e.g. `"Alt+1" => SwitchTab(1)`
+    Values,
+}
+
 struct Event {
     name: Ident,
     attrs: Vec<Attribute>,
-    default_keybindings: String,
+    kind: Kind,
+    /// The key bindings in all modes, `None` if the variant is only bound in some modes
+    default_keybindings: Option<Bindings>,
+    /// The modes and the key bindings in the modes
+    mode_keybindings: Vec<(String, Bindings)>,
+    /// The default key bindings can overlap the ones of other variants, `#[keybind(allow_overlap)]`
+    #[cfg_attr(not(feature = "check"), allow(dead_code))]
+    allow_overlap: bool,
+    /// The deprecated names of the variant in the config, `#[keybind(alias = "old_name")]`
+    aliases: Vec<String>,
+}
+
+/// Check the format of the default key bindings when compiling
+#[cfg(feature = "check")]
+fn check_keybindings(ident: &Ident, kind: &Kind, keybindings: &Bindings) -> Result<()> {
+    let e = match (kind, keybindings) {
+        (Kind::Digit, Bindings::List(keybindings)) => {
+            serde_json::from_str::<crossterm_keybind_core::DigitKeyBindings>(keybindings)
+                .err()
+                .map(|e| e.to_string())
+        }
+        (_, Bindings::List(keybindings)) => {
+            serde_json::from_str::<crossterm_keybind_core::KeyBindings>(keybindings)
+                .err()
+                .map(|e| e.to_string())
+        }
+        (_, Bindings::Values(values)) => values.iter().find_map(|(key, _)| {
+            crossterm_keybind_core::KeySequence::parse(&key.value())
+                .err()
+                .map(|e| e.to_string())
+        }),
+    };
+    if let Some(e) = e {
+        return Err(Error::new(
+            ident.span(),
+            format!("{} Keybinding check fail: {}", ident, e),
+        ));
+    }
+    Ok(())
+}
+
+/// The default key bindings of the events bound by the attribute, e.g. `SwitchTab(1)` and its key
+/// bindings
+#[cfg(feature = "check")]
+fn default_keybindings(
+    e: &Event,
+    keybindings: &Bindings,
+) -> Vec<(String, crossterm_keybind_core::KeyBindings)> {
+    use crossterm_keybind_core::{DigitKeyBindings, KeyBindings, KeySequence};
+
+    match (&e.kind, keybindings) {
+        (Kind::Digit, Bindings::List(kbs)) => {
+            let kbs: DigitKeyBindings = serde_json::from_str(kbs).unwrap_or_default();
+            (0..10)
+                .map(|digit| (format!("{}({})", e.name, digit), kbs.get(digit).cloned().unwrap_or_default()))
+                .collect()
+        }
+        (_, Bindings::List(kbs)) => {
+            vec![(e.name.to_string(), serde_json::from_str(kbs).unwrap_or_default())]
+        }
+        (_, Bindings::Values(pairs)) => {
+            let mut values: Vec<(String, KeyBindings)> = Vec::new();
+            for (key, value) in pairs.iter() {
+                let label = format!("{}({})", e.name, value.args_string());
+                let Ok(seq) = KeySequence::parse(&key.value()) else {
+                    continue;
+                };
+                match values.iter_mut().find(|(l, _)| *l == label) {
+                    Some((_, kbs)) => kbs.push(seq),
+                    None => values.push((label, KeyBindings::from(vec![seq]))),
+                }
+            }
+            values
+        }
+    }
+}
+
+/// Check no two variants are bound to the same key by default, unless the overlap is allowed
+#[cfg(feature = "check")]
+fn check_overlaps(events: &[Event]) -> Result<()> {
+    type Bound<'a> = Vec<(&'a Event, String, crossterm_keybind_core::KeyBindings)>;
+
+    // the events bound in all modes, then the ones bound in each mode
+    let mut scopes: Vec<(Option<&str>, Bound)> = vec![(None, Vec::new())];
+    for e in events.iter().filter(|e| !e.allow_overlap) {
+        if let Some(kbs) = &e.default_keybindings {
+            let bound = default_keybindings(e, kbs).into_iter();
+            scopes[0].1.extend(bound.map(|(label, kbs)| (e, label, kbs)));
+        }
+        for (mode, kbs) in e.mode_keybindings.iter() {
+            let i = match scopes.iter().position(|(m, _)| *m == Some(mode.as_str())) {
+                Some(i) => i,
+                None => {
+                    scopes.push((Some(mode.as_str()), Vec::new()));
+                    scopes.len() - 1
+                }
+            };
+            let bound = default_keybindings(e, kbs).into_iter();
+            scopes[i].1.extend(bound.map(|(label, kbs)| (e, label, kbs)));
+        }
+    }
+    let global = &scopes[0].1;
+    for (mode, bound) in scopes.iter() {
+        // the key bindings in a mode are dispatched together with the ones in all modes
+        let in_all_modes = if mode.is_some() { global.as_slice() } else { &[] };
+        for (i, (e, label, kbs)) in bound.iter().enumerate() {
+            for (other, other_label, other_kbs) in bound[i + 1..].iter().chain(in_all_modes) {
+                // the same value bound in all modes and in the mode, other values of the variant
+                // are different events, e.g. `SwitchTab(1)` and `SwitchTab(2)`
+                if other_label == label {
+                    continue;
+                }
+                let Some(seq) = kbs.iter().find(|seq| other_kbs.iter().any(|o| seq.overlaps(o))) else {
+                    continue;
+                };
+                let scope = mode.map(|m| format!(" in mode {m}")).unwrap_or_default();
+                let message = format!(
+                    "{} and {} are both bound to {:?}{} by default, please add #[keybind(allow_overlap)] if it is intended",
+                    label, other_label, seq, scope
+                );
+                let mut error = Error::new(e.name.span(), &message);
+                error.combine(Error::new(other.name.span(), &message));
+                return Err(error);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Parse `"Alt+1" => SwitchTab(1), "Alt+2" => SwitchTab(2)`, else keep the tokens of a list
+fn parse_bindings(input: syn::parse::ParseStream) -> Result<Bindings> {
+    if !(input.peek(syn::LitStr) && input.peek2(syn::Token![=>])) {
+        let keybindings: proc_macro2::TokenStream = input.parse()?;
+        return Ok(Bindings::List(keybindings.to_string()));
+    }
+    let mut values = Vec::new();
+    while !input.is_empty() {
+        let key: syn::LitStr = input.parse()?;
+        input.parse::<syn::Token![=>]>()?;
+        values.push((key, input.parse()?));
+        if !input.is_empty() {
+            input.parse::<syn::Token![,]>()?;
+        }
+    }
+    Ok(Bindings::Values(values))
+}
+
+/// Parse `mode = "normal", ["j"]` of `#[keybindings(mode = "normal", ["j"])]`, the mode can be
+/// omitted for the key bindings of values, e.g. `#[keybindings("Alt+1" => SwitchTab(1))]`
+fn parse_mode_keybindings(input: syn::parse::ParseStream) -> Result<(Option<String>, Bindings)> {
+    let mode_error = |span| {
+        Error::new(
+            span,
+            "Keybindings is incorrect, for example correct format is #[keybindings(mode = \"normal\", [\"j\"])]",
+        )
+    };
+    if !input.peek(Ident) {
+        let span = input.span();
+        return match parse_bindings(input)? {
+            Bindings::List(_) => Err(mode_error(span)),
+            values => Ok((None, values)),
+        };
+    }
+    let key: Ident = input.parse()?;
+    if key != "mode" {
+        return Err(mode_error(key.span()));
+    }
+    input.parse::<syn::Token![=]>()?;
+    let mode: syn::LitStr = input.parse()?;
+    if syn::parse_str::<Ident>(&mode.value()).is_err() {
+        return Err(Error::new(
+            mode.span(),
+            "Mode should be a snake case name, e.g. \"normal\"",
+        ));
+    }
+    input.parse::<syn::Token![,]>()?;
+    Ok((Some(mode.value()), parse_bindings(input)?))
 }
 
 impl Event {
@@ -17,14 +270,11 @@ impl Event {
             ..
         }: Variant,
     ) -> Result<Event> {
-        if fields != Fields::Unit {
-            return Err(Error::new(
-                ident.span(),
-                "KeyBind derive only use for enum without fields",
-            ));
-        }
         let mut new_attrs = Vec::new();
-        let mut default_keybindings = "[]".to_string();
+        let mut default_keybindings = None;
+        let mut mode_keybindings = Vec::new();
+        let mut allow_overlap = false;
+        let mut aliases = Vec::new();
         for attr in attrs.into_iter() {
             if attr.path().is_ident("keybindings") {
                 let Meta::List(ref meta_list) = attr.meta else {
@@ -33,37 +283,213 @@ impl Event {
                         "Keybindings is incorrect, for example correct format is #[keybings[\"Control+c\",\"Q\"]]",
                     ));
                 };
-                let default_keybindings_str = format!("{}", meta_list.to_token_stream())[11..]
-                    .trim()
-                    .to_string();
-
-                #[cfg(feature = "check")]
-                if let Err(e) = serde_json::from_str::<crossterm_keybind_core::KeyBindings>(
-                    &default_keybindings_str,
-                ) {
+                if let syn::MacroDelimiter::Paren(_) = meta_list.delimiter {
+                    let (mode, keybindings) = meta_list.parse_args_with(parse_mode_keybindings)?;
+                    let Some(mode) = mode else {
+                        default_keybindings = Some(keybindings);
+                        continue;
+                    };
+                    if mode_keybindings.iter().any(|(m, _)| *m == mode) {
+                        return Err(Error::new(
+                            ident.span(),
+                            format!("{} has more than one keybindings in mode {}", ident, mode),
+                        ));
+                    }
+                    mode_keybindings.push((mode, keybindings));
+                    continue;
+                }
+                default_keybindings = match meta_list.parse_args_with(parse_bindings)? {
+                    Bindings::List(_) => Some(Bindings::List(
+                        format!("{}", meta_list.to_token_stream())[11..]
+                            .trim()
+                            .to_string(),
+                    )),
+                    values => Some(values),
+                };
+            } else if attr.path().is_ident("keybind") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("allow_overlap") {
+                        allow_overlap = true;
+                        Ok(())
+                    } else if meta.path.is_ident("alias") {
+                        let alias: syn::LitStr = meta.value()?.parse()?;
+                        if syn::parse_str::<Ident>(&alias.value()).is_err() {
+                            return Err(Error::new(
+                                alias.span(),
+                                "Alias should be a snake case name, e.g. \"toggle_help_widget\"",
+                            ));
+                        }
+                        aliases.push(alias.value());
+                        Ok(())
+                    } else {
+                        Err(meta.error(
+                            "Keybind attribute is incorrect, for example correct format is #[keybind(allow_overlap)] or #[keybind(alias = \"old_name\")]",
+                        ))
+                    }
+                })?;
+            } else {
+                new_attrs.push(attr)
+            }
+        }
+
+        let mut all_keybindings = default_keybindings
+            .iter()
+            .chain(mode_keybindings.iter().map(|(_, kbs)| kbs));
+        let kind = match fields {
+            Fields::Unit => {
+                if all_keybindings.any(|kbs| matches!(kbs, Bindings::Values(_))) {
                     return Err(Error::new(
                         ident.span(),
-                        format!("{} Keybinding check fail: {}", ident, e),
+                        format!("{} without data can not be bound to values", ident),
+                    ));
+                }
+                Kind::Unit
+            }
+            _ if all_keybindings.all(|kbs| matches!(kbs, Bindings::Values(_))) => Kind::Values,
+            Fields::Unnamed(ref unnamed) if unnamed.unnamed.len() == 1 => Kind::Digit,
+            _ => {
+                return Err(Error::new(
+                    ident.span(),
+                    format!(
+                        "{} with data should be bound to values, e.g. #[keybindings(\"Alt+1\" => {}(1))], or bound with <digit> for a single field, e.g. #[keybindings[\"Alt+<digit>\"]]",
+                        ident, ident
+                    ),
+                ));
+            }
+        };
+        if kind == Kind::Digit
+            && default_keybindings
+                .iter()
+                .chain(mode_keybindings.iter().map(|(_, kbs)| kbs))
+                .any(|kbs| matches!(kbs, Bindings::Values(_)))
+        {
+            return Err(Error::new(
+                ident.span(),
+                format!("{} can not be bound with both <digit> and values", ident),
+            ));
+        }
+        for kbs in default_keybindings
+            .iter()
+            .chain(mode_keybindings.iter().map(|(_, kbs)| kbs))
+        {
+            if let Bindings::Values(values) = kbs {
+                if let Some((_, value)) = values.iter().find(|(_, value)| value.ident != ident) {
+                    return Err(Error::new(
+                        value.ident.span(),
+                        format!("{} can only be bound to the values of itself", ident),
                     ));
                 }
-
-                default_keybindings = default_keybindings_str;
-            } else {
-                new_attrs.push(attr)
             }
+            #[cfg(feature = "check")]
+            check_keybindings(&ident, &kind, kbs)?;
+        }
+
+        if kind == Kind::Unit && default_keybindings.is_none() && mode_keybindings.is_empty() {
+            default_keybindings = Some(Bindings::List("[]".to_string()));
         }
         Ok(Event {
             name: ident,
             attrs: new_attrs,
+            kind,
             default_keybindings,
+            mode_keybindings,
+            allow_overlap,
+            aliases,
         })
     }
 }
 
+/// The fields of a config table, and the positions bound by the fields
+#[derive(Default)]
+struct Table {
+    lowers: Vec<Ident>,
+    attrs: Vec<Vec<Attribute>>,
+    types: Vec<proc_macro2::TokenStream>,
+    defaults: Vec<proc_macro2::TokenStream>,
+    /// The positions and the key bindings of them in the table, e.g. `switch_tab.get(1)`
+    bound: Vec<(usize, proc_macro2::TokenStream)>,
+}
+
+impl Table {
+    /// Add the fields of the key bindings of the event, which start from the position
+    fn bind(
+        &mut self,
+        e: &Event,
+        lower: &Ident,
+        first: usize,
+        keybindings: &Bindings,
+        values: &[(String, Ident)],
+    ) {
+        match (&e.kind, keybindings) {
+            (Kind::Unit, Bindings::List(kbs)) => {
+                self.lowers.push(lower.clone());
+                self.attrs.push(e.attrs.clone());
+                self.types.push(quote! { crossterm_keybind::KeyBindings });
+                self.defaults.push(kbs.parse().unwrap());
+                self.bound.push((first, quote! { #lower.clone() }));
+            }
+            (Kind::Digit, Bindings::List(kbs)) => {
+                self.lowers.push(lower.clone());
+                self.attrs.push(e.attrs.clone());
+                self.types.push(quote! { crossterm_keybind::DigitKeyBindings });
+                self.defaults.push(kbs.parse().unwrap());
+                for digit in 0..10u8 {
+                    let lit = proc_macro2::Literal::u8_unsuffixed(digit);
+                    self.bound.push((first + digit as usize, quote! { #lower.get(#lit).cloned().unwrap_or_default() }));
+                }
+            }
+            (_, Bindings::List(_)) => unreachable!(),
+            (_, Bindings::Values(pairs)) => {
+                let mut documented = false;
+                for (i, (args, field)) in values.iter().enumerate() {
+                    let keys: Vec<_> = pairs
+                        .iter()
+                        .filter(|(_, value)| value.args_string() == *args)
+                        .map(|(key, _)| key)
+                        .collect();
+                    if keys.is_empty() {
+                        continue;
+                    }
+                    self.lowers.push(field.clone());
+                    // the values of a variant share the doc of it
+                    self.attrs.push(if documented { Vec::new() } else { e.attrs.clone() });
+                    documented = true;
+                    self.types.push(quote! { crossterm_keybind::KeyBindings });
+                    self.defaults.push(quote! { [#( #keys ),*] });
+                    self.bound.push((first + i, quote! { #field.clone() }));
+                }
+            }
+        }
+    }
+
+    /// The key bindings of each position from the table, which is at the path in the config
+    fn key_bindings(
+        &self,
+        path: proc_macro2::TokenStream,
+        len: usize,
+    ) -> Vec<proc_macro2::TokenStream> {
+        (0..len)
+            .map(|position| match self.bound.iter().find(|(p, _)| *p == position) {
+                Some((_, kbs)) => quote! { #path.#kbs },
+                None => quote! { crossterm_keybind::KeyBindings::new() },
+            })
+            .collect()
+    }
+}
+
+/// The key bindings in a mode
+struct Mode {
+    name: String,
+    table: Table,
+}
+
 pub(crate) struct Events {
     attrs: Vec<Attribute>,
     name: Ident,
     inner: Vec<Event>,
+    sequence_timeout_ms: Option<u64>,
+    /// All the variants can overlap each other, `#[keybind(allow_overlap)]` on the enum
+    allow_overlap: bool,
 }
 
 impl Events {
@@ -75,88 +501,223 @@ impl Events {
             name,
             inner,
             attrs: enum_attrs,
+            sequence_timeout_ms,
+            allow_overlap,
         } = self;
-        let mut fields = Vec::new();
-        let mut lowers = Vec::new();
-        let mut uppers = Vec::new();
-        let mut attrs = Vec::new();
-        let mut defaults = Vec::new();
-
-        for e in inner.into_iter() {
-            let name = e.name.to_string();
-            fields.push(syn::Ident::new(&name, Span::call_site().into()));
-            lowers.push(syn::Ident::new(
-                &name.from_case(Case::UpperCamel).to_case(Case::Snake),
-                Span::call_site().into(),
-            ));
-            uppers.push(syn::Ident::new(
-                &name.from_case(Case::UpperCamel).to_case(Case::Constant),
+        // the events of the positions, a variant with data takes a position for each value
+        let mut events = Vec::new();
+        let mut table = Table::default();
+        let mut modes: Vec<Mode> = Vec::new();
+        // the deprecated names and the current names of the fields in the config
+        let mut aliases: Vec<(String, String)> = Vec::new();
+        // the positions of the events allowed to overlap the others
+        let mut overlap_allowed = Vec::new();
+
+        for e in inner.iter() {
+            let ident = &e.name;
+            let lower = syn::Ident::new(
+                &ident.to_string().from_case(Case::UpperCamel).to_case(Case::Snake),
                 Span::call_site().into(),
-            ));
-            attrs.push(e.attrs);
-            let default_stream: proc_macro2::TokenStream = e.default_keybindings.parse().unwrap();
-            defaults.push(default_stream);
+            );
+            let first = events.len();
+            // the arguments and the field names of the values of a variant with data
+            let mut values: Vec<(String, Ident)> = Vec::new();
+            match e.kind {
+                Kind::Unit => events.push(quote! { #name::#ident }),
+                Kind::Digit => {
+                    for digit in 0..10u8 {
+                        let lit = proc_macro2::Literal::u8_unsuffixed(digit);
+                        events.push(quote! { #name::#ident(#lit) });
+                    }
+                }
+                Kind::Values => {
+                    let all_keybindings = e
+                        .default_keybindings
+                        .iter()
+                        .chain(e.mode_keybindings.iter().map(|(_, kbs)| kbs));
+                    for kbs in all_keybindings {
+                        let Bindings::Values(pairs) = kbs else {
+                            continue;
+                        };
+                        for (_, value) in pairs.iter() {
+                            let args = value.args_string();
+                            if values.iter().any(|(a, _)| *a == args) {
+                                continue;
+                            }
+                            let field = value.field(&lower)?;
+                            if values.iter().any(|(_, f)| *f == field) {
+                                return Err(Error::new(
+                                    value.ident.span(),
+                                    format!("{} has more than one value named {}", ident, field),
+                                ));
+                            }
+                            let value_args = &value.args;
+                            events.push(quote! { #name::#ident(#( #value_args ),*) });
+                            values.push((args, field));
+                        }
+                    }
+                }
+            }
+            if allow_overlap || e.allow_overlap {
+                overlap_allowed.extend((first..events.len()).map(proc_macro2::Literal::usize_unsuffixed));
+            }
+            // the deprecated names of the fields, e.g. `old_name_1` of `switch_tab_1`
+            for alias in e.aliases.iter() {
+                let fields = match e.kind {
+                    Kind::Values => values.iter().map(|(_, field)| field.to_string()).collect(),
+                    _ => vec![lower.to_string()],
+                };
+                for field in fields {
+                    let old = format!("{alias}{}", &field[lower.to_string().len()..]);
+                    if !aliases.iter().any(|(o, _)| *o == old) {
+                        aliases.push((old, field));
+                    }
+                }
+            }
+            if let Some(default_keybindings) = &e.default_keybindings {
+                table.bind(e, &lower, first, default_keybindings, &values);
+            }
+            for (mode_name, mode_keybindings) in e.mode_keybindings.iter() {
+                let mode = match modes.iter_mut().position(|m| m.name == *mode_name) {
+                    Some(i) => &mut modes[i],
+                    None => {
+                        modes.push(Mode {
+                            name: mode_name.clone(),
+                            table: Table::default(),
+                        });
+                        modes.last_mut().unwrap()
+                    }
+                };
+                mode.table.bind(e, &lower, first, mode_keybindings, &values);
+            }
+        }
+        let positions: Vec<_> = (0..events.len())
+            .map(proc_macro2::Literal::usize_unsuffixed)
+            .collect();
+        let key_bindings = table.key_bindings(quote! { self }, events.len());
+        let Table {
+            lowers,
+            attrs,
+            types,
+            defaults,
+            ..
+        } = table;
+
+        let mut mode_fields = Vec::new();
+        let mut mode_structs = Vec::new();
+        let mut mode_patches = Vec::new();
+        let mut mode_names = Vec::new();
+        let mut mode_docs = Vec::new();
+        let mut mode_key_bindings = Vec::new();
+        let mut mode_struct_impls = Vec::new();
+        for Mode {
+            name: mode_name,
+            table: mode_table,
+        } in modes.into_iter()
+        {
+            let field = syn::Ident::new(&mode_name, Span::call_site().into());
+            let camel = mode_name.from_case(Case::Snake).to_case(Case::UpperCamel);
+            let mode_struct = syn::Ident::new(&format!("DefaultBinding{camel}"), Span::call_site().into());
+            let mode_patch = syn::Ident::new(&format!("KeyBinding{camel}"), Span::call_site().into());
+            let patch_name = mode_patch.to_string();
+            mode_key_bindings.push(mode_table.key_bindings(quote! { self.#field }, events.len()));
+            let Table {
+                lowers: mode_lowers,
+                attrs: mode_attrs,
+                types: mode_types,
+                defaults: mode_defaults,
+                ..
+            } = mode_table;
+            mode_struct_impls.push(quote! {
+                #[derive(crossterm_keybind::struct_patch::Patch, crossterm_keybind::toml_example::TomlExample, serde::Deserialize, serde::Serialize)]
+                #[patch(name = #patch_name)]
+                #[patch(attribute(derive(serde::Deserialize)))]
+                struct #mode_struct {
+                    #(
+                        #( #mode_attrs )*
+                        #[toml_example(default=#mode_defaults)]
+                        #mode_lowers: #mode_types,
+                    )*
+                }
+            });
+            mode_docs.push(format!(" Key bindings in {mode_name} mode"));
+            mode_names.push(mode_name);
+            mode_fields.push(field);
+            mode_structs.push(mode_struct);
+            mode_patches.push(mode_patch);
         }
 
-        #[cfg(feature = "safety")]
-        let safety_check_para_impl = quote! {
-            static BINDING_INIT: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+        let mode_patch_impl = if mode_fields.is_empty() {
+            quote! {}
+        } else {
+            quote! {
+                #(
+                    if let Some(value) = table.get(#mode_names) {
+                        let patch: #mode_patches =
+                            value.clone().try_into().map_err(|e| crossterm_keybind::Error::load_config_error(&table, e))?;
+                        key_config.#mode_fields.apply(patch);
+                    }
+                )*
+            }
         };
-        #[cfg(not(feature = "safety"))]
-        let safety_check_para_impl = quote! {};
 
-        #[cfg(feature = "safety")]
-        let safety_check_init_impl = quote! {
-            if BINDING_INIT.load(std::sync::atomic::Ordering::Relaxed) {
-                return Err(crossterm_keybind::Error::ConfigDoubleInitError);
-            } else {
-                BINDING_INIT.store(true, std::sync::atomic::Ordering::Release);
+        let (alias_olds, alias_news): (Vec<_>, Vec<_>) = aliases.into_iter().unzip();
+        let alias_impl = if alias_olds.is_empty() {
+            quote! {}
+        } else {
+            quote! {
+                crossterm_keybind::migrate_aliases(&mut table, &[#( (#alias_olds, #alias_news) ),*]);
             }
         };
-        #[cfg(not(feature = "safety"))]
-        let safety_check_init_impl = quote! {};
 
-        #[cfg(feature = "safety")]
-        let safety_check_match_impl = quote! {
-            if !BINDING_INIT.load(std::sync::atomic::Ordering::Acquire) {
-                // NOTE
-                // You are using crossterm in an unexpected way, we prevent UB but not panic
-                // in runtime, please run anyfunction after `init_and_load`/`init_and_load_file`
-                // https://docs.rs/crossterm-keybind/latest/crossterm_keybind/trait.KeyBindTrait.html#tymethod.init_and_load
-                crossterm_keybind::log::warn!("Keybindings are used without initialization, it will never match");
-                return false;
-            }
+        let (timeout_field_impl, timeout_impl) = if let Some(ms) = sequence_timeout_ms {
+            let ms = proc_macro2::Literal::u64_unsuffixed(ms);
+            (
+                quote! {
+                    /// The timeout in milliseconds to wait for the next key stroke of a key sequence
+                    #[toml_example(default=#ms)]
+                    sequence_timeout_ms: u64,
+                },
+                quote! {
+                    Some(std::time::Duration::from_millis(self.sequence_timeout_ms))
+                },
+            )
+        } else {
+            (quote! {}, quote! { None })
         };
-        #[cfg(not(feature = "safety"))]
-        let safety_check_match_impl = quote! {};
 
         #[cfg(feature = "safety")]
-        let safety_check_dispatch_impl = quote! {
-            if !BINDING_INIT.load(std::sync::atomic::Ordering::Acquire) {
-                // NOTE
-                // You are using crossterm in an unexpected way, we prevent UB but not panic
-                // in runtime, please run anyfunction after `init_and_load`/`init_and_load_file`
-                // https://docs.rs/crossterm-keybind/latest/crossterm_keybind/trait.KeyBindTrait.html#tymethod.init_and_load
-                crossterm_keybind::log::warn!("Keybindings are used without initialization, it will never match");
-                return Vec::new();
+        let safety_check_init_impl = quote! {
+            if Self::keymap().is_some() {
+                return Err(crossterm_keybind::Error::ConfigDoubleInitError);
             }
         };
         #[cfg(not(feature = "safety"))]
-        let safety_check_dispatch_impl = quote! {};
+        let safety_check_init_impl = quote! {};
 
         #[cfg(feature = "safety")]
-        let safety_check_display_impl = quote! {
-            if !BINDING_INIT.load(std::sync::atomic::Ordering::Acquire) {
-                // NOTE
-                // You are using crossterm in an unexpected way, we prevent UB but not panic
-                // in runtime, please run anyfunction after `init_and_load`/`init_and_load_file`
-                // https://docs.rs/crossterm-keybind/latest/crossterm_keybind/trait.KeyBindTrait.html#tymethod.init_and_load
-                crossterm_keybind::log::warn!("Keybindings are not initialized");
-                return String::new();
+        let keymap_impl = |uninit_output: proc_macro2::TokenStream| {
+            quote! {
+                let Some(keymap) = Self::keymap() else {
+                    // NOTE
+                    // You are using crossterm in an unexpected way, we prevent panic in runtime,
+                    // please run anyfunction after `init_and_load`/`init_and_load_file`
+                    // https://docs.rs/crossterm-keybind/latest/crossterm_keybind/trait.KeyBindTrait.html#tymethod.init_and_load
+                    crossterm_keybind::log::warn!("Keybindings are used without initialization, it will never match");
+                    return #uninit_output;
+                };
             }
         };
         #[cfg(not(feature = "safety"))]
-        let safety_check_display_impl = quote! {};
+        let keymap_impl = |_uninit_output: proc_macro2::TokenStream| {
+            quote! {
+                let keymap = Self::keymap()
+                    .expect("Keybindings are used without initialization, please run `init_and_load`/`init_and_load_file` ahead");
+            }
+        };
+        let keymap_match_impl = keymap_impl(quote! { false });
+        let keymap_display_impl = keymap_impl(quote! { String::new() });
+        let keymap_dispatch_impl = keymap_impl(quote! { Vec::new() });
 
         Ok(quote! {
             use crossterm_keybind::toml_example;
@@ -168,73 +729,143 @@ impl Events {
             #(
                 #enum_attrs
             )*
-            #[derive(crossterm_keybind::struct_patch::Patch, crossterm_keybind::toml_example::TomlExample, serde::Deserialize)]
+            #[derive(crossterm_keybind::struct_patch::Patch, crossterm_keybind::toml_example::TomlExample, serde::Deserialize, serde::Serialize)]
             #[patch(name = "KeyBinding")]
             #[patch(attribute(derive(serde::Deserialize)))]
             struct DefaultBinding {
+                /// Start from the default key bindings, with `false` every key binding starts from empty
+                /// and only the ones in this file are bound. A key binding is unbound with an empty
+                /// list, e.g. `quit = []`.
+                #[toml_example(default=true)]
+                inherit_defaults: bool,
+                #timeout_field_impl
                 #(
                     #( #attrs )*
                     #[toml_example(default=#defaults)]
-                    #lowers: crossterm_keybind::KeyBindings,
+                    #lowers: #types,
+                )*
+                #(
+                    #[doc = #mode_docs]
+                    #[toml_example(nesting)]
+                    #[patch(skip)]
+                    #mode_fields: #mode_structs,
                 )*
-
             }
 
-            #safety_check_para_impl
-            #(
-                static mut #uppers: core::mem::MaybeUninit<crossterm_keybind::KeyBindings> = core::mem::MaybeUninit::uninit();
-            )*
+            #( #mode_struct_impls )*
 
-            impl crossterm_keybind::KeyBindTrait for #name {
-                fn init_and_load_file(patch_path: Option<std::path::PathBuf>) -> Result<(), crossterm_keybind::Error>{
-                    #safety_check_init_impl
+            impl DefaultBinding {
+                /// Load the key map patched with the layers in order, the layers which do not exist are skipped
+                fn load(patches: Vec<Option<toml::Table>>) -> Result<crossterm_keybind::KeyMap<#name>, crossterm_keybind::Error> {
+                    let example = DefaultBinding::toml_example();
                     let mut key_config: DefaultBinding =
-                        toml::from_str(&DefaultBinding::toml_example()).map_err(|e|crossterm_keybind::Error::DefaultConfigError(e.to_string()))?;
-                    if let Some(p) = patch_path {
-                        let contents = std::fs::read_to_string(p).map_err(crossterm_keybind::Error::ReadConfigError)?;
-                        let patch: KeyBinding =
-                            toml::from_str(&contents).map_err(|e|crossterm_keybind::Error::LoadConfigError(e.to_string()))?;
+                        toml::from_str(&example).map_err(|e| crossterm_keybind::Error::DefaultConfigError(e.to_string()))?;
+                    let mut defaults = key_config.keymap();
+                    let mut keymap = key_config.keymap();
+                    let default_config = toml::Table::try_from(&key_config)
+                        .map_err(|e| crossterm_keybind::Error::DefaultConfigError(e.to_string()))?;
+                    for (layer, table) in patches.into_iter().enumerate() {
+                        let Some(mut table) = table else {
+                            continue;
+                        };
+                        #alias_impl
+                        // the list patches of a layer are computed against the layers below it
+                        let current = toml::Table::try_from(&key_config)
+                            .map_err(|e| crossterm_keybind::Error::DefaultConfigError(e.to_string()))?;
+                        crossterm_keybind::apply_list_patches(&mut table, &current, &default_config)?;
+                        let patch: KeyBinding = table
+                            .clone()
+                            .try_into()
+                            .map_err(|e| crossterm_keybind::Error::load_config_error(&table, e))?;
                         key_config.apply(patch);
+                        #mode_patch_impl
+                        keymap = key_config.keymap().with_layer(&keymap, layer);
                     }
-
-                    unsafe {
-                        #(
-                            #uppers = core::mem::MaybeUninit::new(key_config.#lowers);
-                        )*
+                    if !key_config.inherit_defaults {
+                        // every key binding is from the user
+                        defaults = crossterm_keybind::KeyMap::from_key_bindings(Vec::new(), None);
                     }
+                    Ok(keymap.with_defaults(defaults))
+                }
 
-                    Ok(())
+                fn keymap(&self) -> crossterm_keybind::KeyMap<#name> {
+                    let timeout = #timeout_impl;
+                    crossterm_keybind::KeyMap::from_key_bindings(
+                        vec![#( #key_bindings ),*],
+                        timeout,
+                    )#(
+                        .with_mode(#mode_names, vec![#( #mode_key_bindings ),*])
+                    )*
+                    .with_overlap_allowed(vec![#( #overlap_allowed ),*])
                 }
+            }
 
-                fn init_and_load<T: crossterm_keybind::serde::Serialize>(patch_table: Option<T>) -> Result<(), crossterm_keybind::Error> {
-                    #safety_check_init_impl
-                    let mut key_config: DefaultBinding =
-                        toml::from_str(&DefaultBinding::toml_example()).map_err(|e| crossterm_keybind::Error::DefaultConfigError(e.to_string()))?;
-                    if let Some(table) = patch_table {
-                        let contents = toml::to_string(&table)
-                            .map_err(|e| crossterm_keybind::Error::LoadConfigError(e.to_string()))?;
-                        let patch: KeyBinding =
-                            toml::from_str(&contents).map_err(|e| crossterm_keybind::Error::LoadConfigError(e.to_string()))?;
-                        key_config.apply(patch);
+            static KEYMAP: std::sync::RwLock<Option<std::sync::Arc<crossterm_keybind::KeyMap<#name>>>> =
+                std::sync::RwLock::new(None);
+
+            impl crossterm_keybind::KeyMapEvent for #name {
+                fn position(&self) -> Option<usize> {
+                    match self {
+                        #(
+                            #events => Some(#positions),
+                        )*
+                        // the values of variants with data which are not bound to any key
+                        #[allow(unreachable_patterns)]
+                        _ => None,
                     }
+                }
 
-                    unsafe {
+                fn from_position(position: usize) -> Self {
+                    match position {
                         #(
-                            #uppers = core::mem::MaybeUninit::new(key_config.#lowers);
+                            #positions => #events,
                         )*
+                        _ => unreachable!(),
                     }
+                }
+            }
+
+            impl crossterm_keybind::KeyMapSource for #name {
+                const ALIASES: &'static [(&'static str, &'static str)] = &[#( (#alias_olds, #alias_news) ),*];
+
+                fn keymap_slot() -> &'static std::sync::RwLock<Option<std::sync::Arc<crossterm_keybind::KeyMap<Self>>>> {
+                    &KEYMAP
+                }
 
+                fn load_patches(patches: &[Option<&str>]) -> Result<crossterm_keybind::KeyMap<Self>, crossterm_keybind::Error> {
+                    let patches = patches
+                        .iter()
+                        .map(|contents| {
+                            contents
+                                .map(toml::from_str)
+                                .transpose()
+                                .map_err(|e| crossterm_keybind::Error::LoadConfigError(e.to_string()))
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    DefaultBinding::load(patches)
+                }
+
+                fn load_table(patch: toml::Table) -> Result<crossterm_keybind::KeyMap<Self>, crossterm_keybind::Error> {
+                    DefaultBinding::load(vec![Some(patch)])
+                }
+            }
+
+            impl crossterm_keybind::KeyBindTrait for #name {
+                fn init_and_load_file(patch_path: Option<std::path::PathBuf>) -> Result<(), crossterm_keybind::Error>{
+                    #safety_check_init_impl
+                    Self::set_keymap(Self::load_keymap_file(patch_path)?);
+                    Ok(())
+                }
+
+                fn init_and_load<T: crossterm_keybind::serde::Serialize>(patch_table: Option<T>) -> Result<(), crossterm_keybind::Error> {
+                    #safety_check_init_impl
+                    Self::set_keymap(Self::load_keymap(patch_table)?);
                     Ok(())
                 }
 
                 fn match_any(&self, key_event: &crossterm_keybind::event::KeyEvent) -> bool {
-                    #safety_check_match_impl
-                    use #name as E;
-                    match self {
-                        #(
-                            E::#fields => unsafe { #uppers.assume_init_ref() }.match_any(key_event),
-                        )*
-                    }
+                    #keymap_match_impl
+                    keymap.match_any(self, key_event)
                 }
 
                 fn toml_example() -> String {
@@ -246,38 +877,18 @@ impl Events {
                 }
 
                 fn key_bindings_display(&self) -> String {
-                    #safety_check_display_impl
-                    match self {
-                        #(
-                            #name::#fields => format!("{}", unsafe { #uppers.assume_init_ref() }),
-                        )*
-                    }
+                    #keymap_display_impl
+                    keymap.key_bindings_display(self)
                 }
 
                 fn key_bindings_display_with_format(&self, f: &crossterm_keybind::DisplayFormat) -> String {
-                    #safety_check_display_impl
-                    match self {
-                        #(
-                            #name::#fields => {
-                                match f {
-                                    crossterm_keybind::DisplayFormat::Symbols => format!("{}", unsafe { #uppers.assume_init_ref() }),
-                                    crossterm_keybind::DisplayFormat::Debug => format!("{:?}", unsafe { #uppers.assume_init_ref() }),
-                                    _f => unsafe { #uppers.assume_init_ref() }.display(_f),
-                                }
-                            },
-                        )*
-                    }
+                    #keymap_display_impl
+                    keymap.key_bindings_display_with_format(self, f)
                 }
 
                 fn dispatch(key_event: &crossterm_keybind::event::KeyEvent) -> Vec<Self> {
-                    let mut output = Vec::new();
-                    #safety_check_dispatch_impl
-                    #(
-                        if unsafe { #uppers.assume_init_ref() }.match_any(key_event) {
-                            output.push(#name::#fields);
-                        }
-                    )*
-                    output
+                    #keymap_dispatch_impl
+                    keymap.dispatch(key_event)
                 }
             }
 
@@ -301,10 +912,86 @@ impl Events {
             inner.push(Event::from_variant(v)?);
         }
 
+        let mut sequence_timeout_ms = None;
+        let mut allow_overlap = false;
+        let mut new_attrs = Vec::new();
+        for attr in attrs.into_iter() {
+            if attr.path().is_ident("keybind") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("sequence_timeout_ms") {
+                        let ms: syn::LitInt = meta.value()?.parse()?;
+                        sequence_timeout_ms = Some(ms.base10_parse::<u64>()?);
+                        Ok(())
+                    } else if meta.path.is_ident("allow_overlap") {
+                        allow_overlap = true;
+                        Ok(())
+                    } else {
+                        Err(meta.error(
+                            "Keybind attribute is incorrect, for example correct format is #[keybind(sequence_timeout_ms = 1000)] or #[keybind(allow_overlap)]",
+                        ))
+                    }
+                })?;
+            } else {
+                new_attrs.push(attr)
+            }
+        }
+
+        #[cfg(feature = "check")]
+        if !allow_overlap {
+            check_overlaps(&inner)?;
+        }
+
         Ok(Events {
             name: ident,
             inner,
-            attrs,
+            attrs: new_attrs,
+            sequence_timeout_ms,
+            allow_overlap,
         })
     }
 }
+
+#[cfg(all(test, feature = "check"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_overlaps_of_values() {
+        let overlapping: DeriveInput = syn::parse_quote! {
+            enum Action {
+                #[keybindings("Alt+1" => SwitchTab(1), "Alt+1" => SwitchTab(2))]
+                SwitchTab(u8),
+            }
+        };
+        let Err(e) = Events::from_ast(overlapping.clone()) else {
+            panic!("SwitchTab(1) and SwitchTab(2) overlap");
+        };
+        assert!(e.to_string().starts_with("SwitchTab(1) and SwitchTab(2) are both bound to"));
+
+        let mut allowed = overlapping;
+        allowed.attrs = vec![syn::parse_quote!(#[keybind(allow_overlap)])];
+        assert!(Events::from_ast(allowed).is_ok(), "the overlaps are allowed on the enum");
+
+        let distinct: DeriveInput = syn::parse_quote! {
+            enum Action {
+                #[keybindings["q"]]
+                Quit,
+                #[keybindings["q"]]
+                Close,
+            }
+        };
+        let Err(e) = Events::from_ast(distinct) else {
+            panic!("Quit and Close overlap without any attribute");
+        };
+        assert!(e.to_string().starts_with("Quit and Close are both bound to"));
+
+        let digits: DeriveInput = syn::parse_quote! {
+            enum Action {
+                #[keybindings["Alt+<digit>"]]
+                #[keybindings(mode = "normal", ["Alt+<digit>"])]
+                SwitchTab(u8),
+            }
+        };
+        assert!(Events::from_ast(digits).is_ok());
+    }
+}
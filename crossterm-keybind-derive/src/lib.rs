@@ -1,9 +1,8 @@
 mod key_bind;
 
-#[proc_macro_derive(KeyBind, attributes(keybindings))]
+#[proc_macro_derive(KeyBind, attributes(keybindings, keybind))]
 pub fn derive_patch(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     key_bind::Events::from_ast(syn::parse_macro_input!(item as syn::DeriveInput))
-        .unwrap()
-        .into_token_stream()
-        .unwrap()
+        .and_then(key_bind::Events::into_token_stream)
+        .unwrap_or_else(|e| e.to_compile_error().into())
 }
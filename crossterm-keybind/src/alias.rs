@@ -0,0 +1,90 @@
+use crate::Error;
+use std::ops::Range;
+use std::path::Path;
+use toml::de::{DeTable, DeValue};
+
+/// Rename the deprecated names in a table, the prefix is the table of the mode, e.g. `normal.`
+fn rename(table: &mut toml::Table, aliases: &[(&str, &str)], prefix: &str) -> bool {
+    let mut changed = false;
+    for (alias, name) in aliases.iter() {
+        let Some(value) = table.remove(*alias) else {
+            continue;
+        };
+        changed = true;
+        if table.contains_key(*name) {
+            log::warn!(
+                "`{prefix}{alias}` in the keybind config is deprecated and ignored, because `{prefix}{name}` is set"
+            );
+        } else {
+            log::warn!("`{prefix}{alias}` in the keybind config is deprecated, please rename it to `{prefix}{name}`");
+            table.insert(name.to_string(), value);
+        }
+    }
+    changed
+}
+
+/// Rename the deprecated names of the events to the current ones before the config is patched,
+/// return `true` if any is renamed
+#[doc(hidden)]
+pub fn migrate_aliases(table: &mut toml::Table, aliases: &[(&str, &str)]) -> bool {
+    let mut changed = rename(table, aliases, "");
+    for (mode, value) in table.iter_mut() {
+        if let Some(mode_table) = value.as_table_mut() {
+            changed |= rename(mode_table, aliases, &format!("{mode}."));
+        }
+    }
+    changed
+}
+
+/// The spans of the deprecated names in a table and the current names of them, a deprecated name
+/// is kept if the current name is also set
+fn renamed_spans<'a>(table: &DeTable, aliases: &[(&str, &'a str)], spans: &mut Vec<(Range<usize>, &'a str)>) {
+    for key in table.keys() {
+        let Some((_, name)) = aliases.iter().find(|(alias, _)| *alias == key.get_ref()) else {
+            continue;
+        };
+        if !table.keys().any(|k| k.get_ref() == name) {
+            spans.push((key.span(), name));
+        }
+    }
+}
+
+/// Rename the deprecated names in the text of a config, keeping the comments and the layout,
+/// `None` if there is nothing to rename. The keys are found in the parsed table, so the dotted
+/// keys, e.g. `normal.old = [...]`, the quoted keys and the inline tables are renamed as well.
+fn rewrite_aliases(contents: &str, aliases: &[(&str, &str)]) -> Result<Option<String>, Error> {
+    let table = DeTable::parse(contents).map_err(|e| Error::LoadConfigError(e.to_string()))?;
+    let table = table.get_ref();
+    let mut spans = Vec::new();
+    renamed_spans(table, aliases, &mut spans);
+    for value in table.values() {
+        if let DeValue::Table(mode_table) = value.get_ref() {
+            renamed_spans(mode_table, aliases, &mut spans);
+        }
+    }
+    if spans.is_empty() {
+        return Ok(None);
+    }
+    spans.sort_by_key(|(span, _)| span.start);
+    let mut rewritten = String::with_capacity(contents.len());
+    let mut last = 0;
+    for (span, name) in spans {
+        rewritten.push_str(&contents[last..span.start]);
+        rewritten.push_str(name);
+        last = span.end;
+    }
+    rewritten.push_str(&contents[last..]);
+    Ok(Some(rewritten))
+}
+
+/// Rename the deprecated names in the config file, see
+/// [`crate::KeyBindTrait::migrate_config_file`]
+#[doc(hidden)]
+pub fn migrate_config_file(path: &Path, aliases: &[(&str, &str)]) -> Result<bool, Error> {
+    let contents = std::fs::read_to_string(path)?;
+    let Some(rewritten) = rewrite_aliases(&contents, aliases)? else {
+        return Ok(false);
+    };
+    std::fs::write(path, rewritten)?;
+    Ok(true)
+}
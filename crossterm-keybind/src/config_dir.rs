@@ -0,0 +1,68 @@
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// The directory of the config files, `$XDG_CONFIG_HOME` if it is an absolute path, else
+/// `$HOME/.config`
+fn config_dir(xdg_config_home: Option<OsString>, home: Option<OsString>) -> Option<PathBuf> {
+    xdg_config_home
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| {
+            home.filter(|home| !home.is_empty())
+                .map(|home| PathBuf::from(home).join(".config"))
+        })
+}
+
+/// Expand the leading `~` of the path to the home directory
+fn expand_tilde_with(path: &Path, home: Option<OsString>) -> PathBuf {
+    let Ok(rest) = path.strip_prefix("~") else {
+        return path.to_path_buf();
+    };
+    match home.filter(|home| !home.is_empty()) {
+        Some(home) => PathBuf::from(home).join(rest),
+        None => path.to_path_buf(),
+    }
+}
+
+/// The path of a config file of the app, e.g. `~/.config/myapp/keybinds.toml`, from
+/// `$XDG_CONFIG_HOME` or `$HOME/.config`, `None` if neither of them is set
+pub fn config_file_path(app_name: &str, file_name: &str) -> Option<PathBuf> {
+    config_dir(std::env::var_os("XDG_CONFIG_HOME"), std::env::var_os("HOME"))
+        .map(|dir| dir.join(app_name).join(file_name))
+}
+
+/// Expand the leading `~` of the path to `$HOME`, e.g. `~/.config/myapp/keybinds.toml`, the path
+/// is kept if it does not start with `~` or `$HOME` is not set
+pub fn expand_tilde(path: impl AsRef<Path>) -> PathBuf {
+    expand_tilde_with(path.as_ref(), std::env::var_os("HOME"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_config_dir_and_tilde() {
+        let home = || Some(OsString::from("/home/me"));
+        assert_eq!(
+            config_dir(Some("/xdg".into()), home()),
+            Some(PathBuf::from("/xdg"))
+        );
+        assert_eq!(
+            config_dir(Some("relative".into()), home()),
+            Some(PathBuf::from("/home/me/.config"))
+        );
+        assert_eq!(config_dir(Some("".into()), home()), Some(PathBuf::from("/home/me/.config")));
+        assert_eq!(config_dir(None, Some("".into())), None);
+        assert_eq!(config_dir(None, None), None);
+
+        assert_eq!(
+            expand_tilde_with(Path::new("~/.config/myapp/keybinds.toml"), home()),
+            PathBuf::from("/home/me/.config/myapp/keybinds.toml")
+        );
+        assert_eq!(expand_tilde_with(Path::new("~"), home()), PathBuf::from("/home/me"));
+        assert_eq!(expand_tilde_with(Path::new("~other/keys.toml"), home()), PathBuf::from("~other/keys.toml"));
+        assert_eq!(expand_tilde_with(Path::new("/etc/keys.toml"), home()), PathBuf::from("/etc/keys.toml"));
+        assert_eq!(expand_tilde_with(Path::new("~/keys.toml"), None), PathBuf::from("~/keys.toml"));
+    }
+}
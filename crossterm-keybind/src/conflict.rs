@@ -0,0 +1,160 @@
+use crate::{KeyBinding, KeyMapEvent, KeySequence};
+use std::fmt;
+
+/// Where a key binding comes from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Origin {
+    /// The default key bindings of the enum
+    Default,
+    /// The patch of the user, e.g. the config file
+    User,
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::Default => write!(f, "default"),
+            Origin::User => write!(f, "user"),
+        }
+    }
+}
+
+/// The kind of a [`KeyConflict`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConflictKind {
+    /// The key is bound to more than one event
+    Duplicate,
+    /// The key is bound to an event, and it is the first key strokes of the key sequences of
+    /// other events, e.g. `"g"` and `"g g"`
+    ShadowedSequence,
+}
+
+/// An event claiming a key
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyClaim<E> {
+    pub event: E,
+    pub key_binding: KeySequence,
+    pub origin: Origin,
+}
+
+/// A key binding which can not be distinguished in legacy mode, because the terminal reports it
+/// as another key, see [`crate::KeyBindTrait::legacy_ambiguities`]
+#[derive(Debug, PartialEq)]
+pub struct LegacyAmbiguity<E> {
+    pub action: E,
+    pub key_binding: KeyBinding,
+    pub reported_as: KeyBinding,
+}
+
+/// A key claimed by more than one event, see [`crate::KeyBindTrait::conflicts`]
+///
+/// The `when` conditions of the key bindings are not evaluated, so the key bindings only
+/// conflicting in some contexts are reported as well.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyConflict<E> {
+    pub kind: ConflictKind,
+    /// The mode of the key bindings, `None` for the key bindings in all modes
+    pub mode: Option<&'static str>,
+    /// The key claimed by the events
+    pub key_binding: KeySequence,
+    /// The events claiming the key, the claims of the key sequences shadowed by the key are after
+    /// the ones of the key itself
+    pub claims: Vec<KeyClaim<E>>,
+}
+
+impl<E: fmt::Debug> fmt::Display for KeyConflict<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (bound, shadowed): (Vec<_>, Vec<_>) = self
+            .claims
+            .iter()
+            .partition(|claim| claim.key_binding.overlaps(&self.key_binding));
+        write!(f, "{:?} is bound to ", self.key_binding)?;
+        for (i, claim) in bound.iter().enumerate() {
+            let sep = if i == 0 { "" } else { ", " };
+            write!(f, "{sep}{:?} ({})", claim.event, claim.origin)?;
+        }
+        for (i, claim) in shadowed.iter().enumerate() {
+            let sep = if i == 0 { ", which shadows " } else { ", " };
+            write!(
+                f,
+                "{sep}{:?} of {:?} ({})",
+                claim.key_binding, claim.event, claim.origin
+            )?;
+        }
+        if let Some(mode) = self.mode {
+            write!(f, " in mode {mode}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A key binding of the event at the position
+#[derive(Clone)]
+pub(crate) struct Claim {
+    pub position: usize,
+    pub key_binding: KeySequence,
+    pub origin: Origin,
+    /// The key binding is in the mode rather than in all modes
+    pub in_mode: bool,
+}
+
+impl Claim {
+    fn into_key_claim<E: KeyMapEvent>(self) -> KeyClaim<E> {
+        KeyClaim {
+            event: E::from_position(self.position),
+            key_binding: self.key_binding,
+            origin: self.origin,
+        }
+    }
+}
+
+/// The conflicts among the key bindings dispatched together, the ones of a mode are only reported
+/// if a key binding in the mode is involved
+pub(crate) fn find_conflicts<E: KeyMapEvent>(
+    mode: Option<&'static str>,
+    claims: &[Claim],
+) -> Vec<KeyConflict<E>> {
+    let mut conflicts = Vec::new();
+    let mut grouped = vec![false; claims.len()];
+    for (i, claim) in claims.iter().enumerate() {
+        if grouped[i] {
+            continue;
+        }
+        let mut group = Vec::new();
+        for (j, other) in claims.iter().enumerate().skip(i) {
+            if !grouped[j] && other.key_binding.overlaps(&claim.key_binding) {
+                grouped[j] = true;
+                group.push(other);
+            }
+        }
+        let involved = |claims: &[&Claim]| mode.is_none() || claims.iter().any(|c| c.in_mode);
+
+        if group.iter().any(|c| c.position != claim.position) && involved(&group) {
+            conflicts.push(KeyConflict {
+                kind: ConflictKind::Duplicate,
+                mode,
+                key_binding: claim.key_binding.clone(),
+                claims: group.iter().map(|c| (*c).clone().into_key_claim()).collect(),
+            });
+        }
+
+        let shadowed: Vec<&Claim> = claims
+            .iter()
+            .filter(|c| claim.key_binding.is_prefix_of(&c.key_binding))
+            .filter(|c| group.iter().all(|g| g.position != c.position))
+            .collect();
+        if !shadowed.is_empty() && involved(&[group.as_slice(), shadowed.as_slice()].concat()) {
+            conflicts.push(KeyConflict {
+                kind: ConflictKind::ShadowedSequence,
+                mode,
+                key_binding: claim.key_binding.clone(),
+                claims: group
+                    .iter()
+                    .chain(shadowed.iter())
+                    .map(|c| (*c).clone().into_key_claim())
+                    .collect(),
+            });
+        }
+    }
+    conflicts
+}
@@ -0,0 +1,125 @@
+use crate::Error;
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use std::fmt;
+
+/// A value of a config in any format, the nulls are `None`, e.g. `"quit": null` in JSON, which
+/// keeps the default key bindings
+struct PatchValue(Option<toml::Value>);
+
+/// A key of a table in any format, e.g. `1: ["F1"]` in YAML, taken as text
+struct PatchKey(String);
+
+impl<'de> Deserialize<'de> for PatchValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(PatchValueVisitor)
+    }
+}
+
+struct PatchValueVisitor;
+
+impl<'de> Visitor<'de> for PatchValueVisitor {
+    type Value = PatchValue;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a key bind config")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<PatchValue, E> {
+        Ok(PatchValue(Some(toml::Value::Boolean(v))))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<PatchValue, E> {
+        Ok(PatchValue(Some(toml::Value::Integer(v))))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<PatchValue, E> {
+        i64::try_from(v)
+            .map(|v| PatchValue(Some(toml::Value::Integer(v))))
+            .map_err(|_| E::custom(format!("{v} is too large")))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<PatchValue, E> {
+        Ok(PatchValue(Some(toml::Value::Float(v))))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<PatchValue, E> {
+        Ok(PatchValue(Some(toml::Value::String(v.to_string()))))
+    }
+
+    fn visit_unit<E>(self) -> Result<PatchValue, E> {
+        Ok(PatchValue(None))
+    }
+
+    fn visit_none<E>(self) -> Result<PatchValue, E> {
+        Ok(PatchValue(None))
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<PatchValue, D::Error> {
+        PatchValue::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<PatchValue, A::Error> {
+        let mut values = Vec::new();
+        while let Some(PatchValue(value)) = seq.next_element()? {
+            let value = value.ok_or_else(|| de::Error::custom("a key binding can not be null"))?;
+            values.push(value);
+        }
+        Ok(PatchValue(Some(toml::Value::Array(values))))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<PatchValue, A::Error> {
+        let mut table = toml::Table::new();
+        while let Some((PatchKey(key), PatchValue(value))) = map.next_entry()? {
+            if let Some(value) = value {
+                table.insert(key, value);
+            }
+        }
+        Ok(PatchValue(Some(toml::Value::Table(table))))
+    }
+}
+
+impl<'de> Deserialize<'de> for PatchKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(PatchKeyVisitor)
+    }
+}
+
+struct PatchKeyVisitor;
+
+impl Visitor<'_> for PatchKeyVisitor {
+    type Value = PatchKey;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a name of the key bind config")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<PatchKey, E> {
+        Ok(PatchKey(v.to_string()))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<PatchKey, E> {
+        Ok(PatchKey(v.to_string()))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<PatchKey, E> {
+        Ok(PatchKey(v.to_string()))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<PatchKey, E> {
+        Ok(PatchKey(v.to_string()))
+    }
+}
+
+/// Deserialize a patch from any format into a table, `None` for a null patch
+#[doc(hidden)]
+pub fn deserialize_patch<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<toml::Table>, Error> {
+    let PatchValue(value) =
+        PatchValue::deserialize(deserializer).map_err(|e| Error::LoadConfigError(e.to_string()))?;
+    match value {
+        None => Ok(None),
+        Some(toml::Value::Table(table)) => Ok(Some(table)),
+        Some(_) => Err(Error::LoadConfigError(
+            "the key bind config should be a table".to_string(),
+        )),
+    }
+}
@@ -2,10 +2,76 @@
 pub enum Error {
     #[error("can not init keybind config more than once")]
     ConfigDoubleInitError,
+    #[error("can not reload keybind config before it is initialized")]
+    ConfigNotInitError,
+    #[error("can not find the config directory, neither $XDG_CONFIG_HOME nor $HOME is set")]
+    ConfigDirNotFoundError,
     #[error("can not init keybind config with the default keybindings")]
     DefaultConfigError(String),
     #[error("can not read keybind config")]
     ReadConfigError(#[from] std::io::Error),
     #[error("can not load keybind config")]
     LoadConfigError(String),
+    #[error("can not parse the key binding `{key_binding}` of `{action}`")]
+    ParseKeyBindingError {
+        action: String,
+        key_binding: String,
+        #[source]
+        source: crossterm_keybind_core::ParseKeyBindingError,
+    },
+}
+
+impl Error {
+    /// Locate the key binding which can not be parsed in the config, else keep the error of the
+    /// config format
+    #[cfg(feature = "derive")]
+    #[doc(hidden)]
+    pub fn load_config_error(table: &toml::Table, e: impl ToString) -> Self {
+        Self::find_parse_key_binding_error(table, "").unwrap_or_else(|| Error::LoadConfigError(e.to_string()))
+    }
+
+    /// Find the first key binding which can not be parsed, the actions in the tables of modes are
+    /// prefixed with the mode, e.g. `normal.move_down`
+    #[cfg(feature = "derive")]
+    fn find_parse_key_binding_error(table: &toml::Table, prefix: &str) -> Option<Self> {
+        for (action, value) in table.iter() {
+            if let Some(table) = value.as_table() {
+                if let Some(error) = Self::find_parse_key_binding_error(table, &format!("{prefix}{action}.")) {
+                    return Some(error);
+                }
+                continue;
+            }
+            let Some(key_bindings) = value.as_array() else {
+                continue;
+            };
+            for key_binding in key_bindings.iter() {
+                // a key binding with a condition is a table, e.g. `{ key = "Enter", when = "a && !b" }`
+                let (key, when) = match key_binding {
+                    toml::Value::String(key) => (Some(key.as_str()), None),
+                    toml::Value::Table(t) => (
+                        t.get("key").and_then(|k| k.as_str()),
+                        t.get("when").and_then(|w| w.as_str()),
+                    ),
+                    _ => continue,
+                };
+                let error = |key_binding: &str, source| Error::ParseKeyBindingError {
+                    action: format!("{prefix}{action}"),
+                    key_binding: key_binding.to_string(),
+                    source,
+                };
+                // the placeholder of an event with a digit is checked with a digit
+                let parse = |key: &str| {
+                    let digit = key.replace(crossterm_keybind_core::DIGIT_PLACEHOLDER, "0");
+                    crossterm_keybind_core::KeySequence::parse(&digit)
+                };
+                if let Some(Err(source)) = key.map(parse) {
+                    return Some(error(key.unwrap_or_default(), source));
+                }
+                if let Some(Err(source)) = when.map(crossterm_keybind_core::When::parse) {
+                    return Some(error(when.unwrap_or_default(), source));
+                }
+            }
+        }
+        None
+    }
 }
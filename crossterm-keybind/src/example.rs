@@ -0,0 +1,18 @@
+use crate::Error;
+
+/// The table of the key config example generated from the defaults
+fn example_table(toml_example: &str) -> Result<toml::Table, Error> {
+    toml::from_str(toml_example).map_err(|e| Error::DefaultConfigError(e.to_string()))
+}
+
+/// The key config example in JSON, see [`crate::KeyBindTrait::json_example`]
+#[cfg(feature = "json_example")]
+pub(crate) fn json_example(toml_example: &str) -> Result<String, Error> {
+    serde_json::to_string_pretty(&example_table(toml_example)?).map_err(|e| Error::DefaultConfigError(e.to_string()))
+}
+
+/// The key config example in YAML, see [`crate::KeyBindTrait::yaml_example`]
+#[cfg(feature = "yaml_example")]
+pub(crate) fn yaml_example(toml_example: &str) -> Result<String, Error> {
+    serde_yaml::to_string(&example_table(toml_example)?).map_err(|e| Error::DefaultConfigError(e.to_string()))
+}
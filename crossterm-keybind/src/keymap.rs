@@ -0,0 +1,511 @@
+use crate::conflict::{find_conflicts, Claim, KeyConflict, LegacyAmbiguity, Origin};
+use crate::event::KeyEvent;
+use crate::{
+    ConfigLayer, DisplayFormat, KeyBindingIndex, KeyBindings, KeyContext, KeySequenceMatcher,
+};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+/// The mapping between the variants of a key binding enum and their positions in declaration
+/// order, it is implemented by the derive macro.
+///
+/// A variant with data takes a position for each value bound to keys, e.g. `SwitchTab(1)`, and the
+/// values not bound to any key have no position.
+#[doc(hidden)]
+pub trait KeyMapEvent: Sized {
+    fn position(&self) -> Option<usize>;
+
+    fn from_position(position: usize) -> Self;
+}
+
+/// An owned key map of the events `E`, loaded from the default key bindings and a patch with
+/// [`crate::KeyBindTrait::load_keymap`] or [`crate::KeyBindTrait::load_keymap_file`].
+///
+/// Unlike the global key bindings initialized by `init_and_load`/`init_and_load_file`, a key map is
+/// a plain value, so it can be loaded again and replaced when the settings change, e.g. with
+/// [`crate::KeyBindTrait::set_keymap`] for the global one.
+pub struct KeyMap<E> {
+    key_bindings: Vec<KeyBindings>,
+    index: KeyBindingIndex,
+    sequence_timeout: Option<Duration>,
+    modes: Vec<Mode>,
+    file: Option<(PathBuf, Option<SystemTime>)>,
+    /// The layers of the config and the modification times of their files
+    layers: Vec<(ConfigLayer, Option<SystemTime>)>,
+    /// The layer changing the key bindings of each position last, `None` for the defaults
+    provenance: Vec<Option<usize>>,
+    /// The key map of the default key bindings, to tell the origin of the key bindings
+    defaults: Option<Box<KeyMap<E>>>,
+    /// The terminal is in legacy mode, see [`KeyMap::with_legacy_mode`]
+    legacy: bool,
+    /// The positions of the events allowed to overlap the others, `#[keybind(allow_overlap)]`
+    overlap_allowed: Vec<usize>,
+    events: PhantomData<fn() -> E>,
+}
+
+/// The key bindings of each variant in a mode, and the index of them
+struct Mode {
+    name: &'static str,
+    key_bindings: Vec<KeyBindings>,
+    index: KeyBindingIndex,
+    provenance: Vec<Option<usize>>,
+}
+
+/// The key bindings of the events without a position
+static UNBOUND: KeyBindings = KeyBindings::new();
+
+/// The key bindings at the position match the key event in the context, the index only narrows
+/// down the positions by key strokes without evaluating the conditions
+fn holds(
+    key_bindings: &[KeyBindings],
+    position: usize,
+    key_event: &KeyEvent,
+    ctx: &dyn KeyContext,
+    legacy: bool,
+) -> bool {
+    let kbs = &key_bindings[position];
+    !kbs.has_conditions() || kbs.match_any_with_legacy(key_event, ctx, legacy)
+}
+
+/// The key bindings of each position, the ones not in the defaults are from the user, the
+/// positions allowed to overlap the others claim nothing
+fn claims(
+    key_bindings: &[KeyBindings],
+    defaults: Option<&[KeyBindings]>,
+    in_mode: bool,
+    overlap_allowed: &[usize],
+) -> Vec<Claim> {
+    let mut claims = Vec::new();
+    for (position, kbs) in key_bindings.iter().enumerate() {
+        if overlap_allowed.contains(&position) {
+            continue;
+        }
+        // the defaults without the position are empty, e.g. a clean-slate key map
+        let default_kbs = defaults.map(|d| d.get(position));
+        for seq in kbs.iter() {
+            let origin = match default_kbs {
+                Some(d) if !d.is_some_and(|d| d.iter().any(|default| default == seq)) => Origin::User,
+                _ => Origin::Default,
+            };
+            claims.push(Claim {
+                position,
+                key_binding: seq.clone(),
+                origin,
+                in_mode,
+            });
+        }
+    }
+    claims
+}
+
+/// The layer changing each key bindings last, the one of the previous key map is kept if the key
+/// bindings are the same
+fn provenance(
+    key_bindings: &[KeyBindings],
+    previous: Option<(&[KeyBindings], &[Option<usize>])>,
+    layer: usize,
+) -> Vec<Option<usize>> {
+    key_bindings
+        .iter()
+        .enumerate()
+        .map(|(position, kbs)| match previous {
+            Some((previous, layers)) if previous.get(position) == Some(kbs) => layers[position],
+            _ => Some(layer),
+        })
+        .collect()
+}
+
+/// The modification time of the file, `None` if it can not be read
+pub(crate) fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl<E> KeyMap<E> {
+    /// Record the config file the key map is loaded from, the modification time should be taken
+    /// before reading the file, such that a change during reading is detected later.
+    #[doc(hidden)]
+    pub fn with_file(mut self, path: PathBuf, modified: Option<SystemTime>) -> Self {
+        self.file = Some((path, modified));
+        self
+    }
+
+    /// The modification time of the file, used to record it before loading
+    #[doc(hidden)]
+    pub fn modified(path: &Path) -> Option<SystemTime> {
+        modified(path)
+    }
+
+    /// The config file the key map is patched from
+    pub fn path(&self) -> Option<&Path> {
+        self.file.as_ref().map(|(path, _)| path.as_path())
+    }
+
+    /// Record the layers of the config the key map is loaded from, with the modification times
+    /// of their files taken before reading them
+    #[doc(hidden)]
+    pub fn with_layers(mut self, layers: Vec<(ConfigLayer, Option<SystemTime>)>) -> Self {
+        self.layers = layers;
+        self
+    }
+
+    /// The layers of the config the key map is loaded from, see
+    /// [`crate::KeyBindTrait::load_keymap_layers`]
+    pub fn layers(&self) -> impl Iterator<Item = &ConfigLayer> {
+        self.layers.iter().map(|(layer, _)| layer)
+    }
+
+    /// The config file, or a config file of the layers, is modified, created or removed after the
+    /// key map is loaded
+    pub fn is_file_changed(&self) -> bool {
+        self.file
+            .as_ref()
+            .is_some_and(|(path, loaded)| modified(path) != *loaded)
+            || self.layers.iter().any(|(layer, loaded)| match layer {
+                ConfigLayer::File(path) => modified(path) != *loaded,
+                ConfigLayer::Toml { .. } => false,
+            })
+    }
+
+    /// Set the terminal is in legacy mode, i.e. the keyboard enhancement flags of crossterm are not
+    /// pushed, such that the key bindings legacy terminals can not distinguish are folded into the
+    /// same key when matching, e.g. `"Control+i"` and `"Tab"`.
+    pub fn with_legacy_mode(mut self, enabled: bool) -> Self {
+        self.legacy = enabled;
+        self.index.set_legacy_mode(enabled);
+        for mode in self.modes.iter_mut() {
+            mode.index.set_legacy_mode(enabled);
+        }
+        self
+    }
+
+    /// The key map is in legacy mode or not, see [`KeyMap::with_legacy_mode`]
+    pub fn legacy_mode(&self) -> bool {
+        self.legacy
+    }
+}
+
+impl<E: KeyMapEvent> KeyMap<E> {
+    /// The key map from the key bindings of each variant in declaration order
+    #[doc(hidden)]
+    pub fn from_key_bindings(
+        key_bindings: Vec<KeyBindings>,
+        sequence_timeout: Option<Duration>,
+    ) -> Self {
+        let mut index = KeyBindingIndex::new();
+        for (position, kbs) in key_bindings.iter().enumerate() {
+            index.insert(position, kbs);
+        }
+        Self {
+            provenance: vec![None; key_bindings.len()],
+            key_bindings,
+            index,
+            sequence_timeout,
+            modes: Vec::new(),
+            file: None,
+            layers: Vec::new(),
+            defaults: None,
+            legacy: false,
+            overlap_allowed: Vec::new(),
+            events: PhantomData,
+        }
+    }
+
+    /// Add the key bindings of each variant in the mode, in declaration order
+    #[doc(hidden)]
+    pub fn with_mode(mut self, name: &'static str, key_bindings: Vec<KeyBindings>) -> Self {
+        let mut index = KeyBindingIndex::new();
+        index.set_legacy_mode(self.legacy);
+        for (position, kbs) in key_bindings.iter().enumerate() {
+            index.insert(position, kbs);
+        }
+        self.modes.push(Mode {
+            name,
+            provenance: vec![None; key_bindings.len()],
+            key_bindings,
+            index,
+        });
+        self
+    }
+
+    /// The events at the positions are allowed to overlap the others, they are not reported as
+    /// conflicts
+    #[doc(hidden)]
+    pub fn with_overlap_allowed(mut self, positions: Vec<usize>) -> Self {
+        self.overlap_allowed = positions;
+        self
+    }
+
+    /// Record the key map of the default key bindings before the patch is applied
+    #[doc(hidden)]
+    pub fn with_defaults(mut self, defaults: KeyMap<E>) -> Self {
+        self.defaults = Some(Box::new(defaults));
+        self
+    }
+
+    /// Record the layer of the config for the key bindings changed from the previous key map,
+    /// which is loaded from the layers below it
+    #[doc(hidden)]
+    pub fn with_layer(mut self, previous: &KeyMap<E>, layer: usize) -> Self {
+        self.provenance = provenance(
+            &self.key_bindings,
+            Some((&previous.key_bindings, &previous.provenance)),
+            layer,
+        );
+        for mode in self.modes.iter_mut() {
+            let previous = previous
+                .mode(mode.name)
+                .map(|m| (m.key_bindings.as_slice(), m.provenance.as_slice()));
+            mode.provenance = provenance(&mode.key_bindings, previous, layer);
+        }
+        self
+    }
+
+    fn mode(&self, mode: &str) -> Option<&Mode> {
+        self.modes.iter().find(|m| m.name == mode)
+    }
+
+    /// The names of the modes, in the order of their first appearance in the enum
+    pub fn modes(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.modes.iter().map(|m| m.name)
+    }
+
+    /// The key bindings of the event only in the mode, the key bindings in all modes are not
+    /// included, `None` if the mode is unknown
+    pub fn key_bindings_in(&self, mode: &str, event: &E) -> Option<&KeyBindings> {
+        self.mode(mode)
+            .map(|m| event.position().map_or(&UNBOUND, |position| &m.key_bindings[position]))
+    }
+
+    /// Key event match for the key bindings of the event in the mode and in all modes
+    pub fn match_any_in(&self, mode: &str, event: &E, key_event: &KeyEvent) -> bool {
+        self.match_any_in_with(mode, event, key_event, &())
+    }
+
+    /// Key event match for the key bindings of the event in the mode and in all modes, of which
+    /// the conditions hold in the context
+    pub fn match_any_in_with(
+        &self,
+        mode: &str,
+        event: &E,
+        key_event: &KeyEvent,
+        ctx: &dyn KeyContext,
+    ) -> bool {
+        self.key_bindings_in(mode, event)
+            .is_some_and(|kbs| kbs.match_any_with_legacy(key_event, ctx, self.legacy))
+            || self.match_any_with(event, key_event, ctx)
+    }
+
+    /// Dispatch events from the key bindings in the mode, then the ones in all modes
+    pub fn dispatch_in(&self, mode: &str, key_event: &KeyEvent) -> Vec<E> {
+        self.dispatch_in_iter(mode, key_event).collect()
+    }
+
+    /// Dispatch events from the key bindings in the mode, then the ones in all modes, of which the
+    /// conditions hold in the context
+    pub fn dispatch_in_with(&self, mode: &str, key_event: &KeyEvent, ctx: &dyn KeyContext) -> Vec<E> {
+        self.dispatch_in_iter_with(mode, key_event, ctx).collect()
+    }
+
+    /// Dispatch events from the key bindings in the mode, then the ones in all modes, without
+    /// allocation
+    pub fn dispatch_in_iter(&self, mode: &str, key_event: &KeyEvent) -> impl Iterator<Item = E> + '_ {
+        self.dispatch_in_iter_with(mode, key_event, &())
+    }
+
+    /// Dispatch events from the key bindings in the mode, then the ones in all modes, of which the
+    /// conditions hold in the context, without allocation
+    pub fn dispatch_in_iter_with<'a>(
+        &'a self,
+        mode: &str,
+        key_event: &KeyEvent,
+        ctx: &'a dyn KeyContext,
+    ) -> impl Iterator<Item = E> + 'a {
+        let key_event = *key_event;
+        let (in_mode, mode_key_bindings) = self
+            .mode(mode)
+            .map(|m| (m.index.get(&key_event), m.key_bindings.as_slice()))
+            .unwrap_or_default();
+        let in_mode = in_mode
+            .iter()
+            .copied()
+            .filter(move |position| holds(mode_key_bindings, *position, &key_event, ctx, self.legacy));
+        let held_in_mode = in_mode.clone();
+        let in_all_modes = self
+            .index
+            .get(&key_event)
+            .iter()
+            .copied()
+            .filter(move |position| holds(&self.key_bindings, *position, &key_event, ctx, self.legacy))
+            .filter(move |position| held_in_mode.clone().all(|p| p != *position));
+        in_mode.chain(in_all_modes).map(E::from_position)
+    }
+
+    /// The key bindings of the event, empty for a value of a variant with data not bound to any key
+    pub fn key_bindings(&self, event: &E) -> &KeyBindings {
+        event
+            .position()
+            .map_or(&UNBOUND, |position| &self.key_bindings[position])
+    }
+
+    /// The layer of the config changing the key bindings of the event in all modes last, `None`
+    /// if the key bindings are the defaults
+    pub fn layer_of(&self, event: &E) -> Option<&ConfigLayer> {
+        let layer = self.provenance[event.position()?]?;
+        self.layers.get(layer).map(|(layer, _)| layer)
+    }
+
+    /// The layer of the config changing the key bindings of the event only in the mode last,
+    /// `None` if the key bindings are the defaults or the mode is unknown
+    pub fn layer_of_in(&self, mode: &str, event: &E) -> Option<&ConfigLayer> {
+        let layer = self.mode(mode)?.provenance[event.position()?]?;
+        self.layers.get(layer).map(|(layer, _)| layer)
+    }
+
+    /// Key event match for the key bindings of the event
+    pub fn match_any(&self, event: &E, key_event: &KeyEvent) -> bool {
+        self.match_any_with(event, key_event, &())
+    }
+
+    /// Key event match for the key bindings of the event, of which the conditions hold in the
+    /// context
+    pub fn match_any_with(&self, event: &E, key_event: &KeyEvent, ctx: &dyn KeyContext) -> bool {
+        self.key_bindings(event)
+            .match_any_with_legacy(key_event, ctx, self.legacy)
+    }
+
+    /// Dispatch events from the key bindings
+    pub fn dispatch(&self, key_event: &KeyEvent) -> Vec<E> {
+        self.dispatch_iter(key_event).collect()
+    }
+
+    /// Dispatch events from the key bindings of which the conditions hold in the context
+    pub fn dispatch_with(&self, key_event: &KeyEvent, ctx: &dyn KeyContext) -> Vec<E> {
+        self.dispatch_iter_with(key_event, ctx).collect()
+    }
+
+    /// Dispatch events from the key bindings without allocation
+    pub fn dispatch_iter(&self, key_event: &KeyEvent) -> impl Iterator<Item = E> + '_ {
+        self.dispatch_iter_with(key_event, &())
+    }
+
+    /// Dispatch events from the key bindings of which the conditions hold in the context, without
+    /// allocation
+    pub fn dispatch_iter_with<'a>(
+        &'a self,
+        key_event: &KeyEvent,
+        ctx: &'a dyn KeyContext,
+    ) -> impl Iterator<Item = E> + 'a {
+        let key_event = *key_event;
+        self.index
+            .get(&key_event)
+            .iter()
+            .copied()
+            .filter(move |position| holds(&self.key_bindings, *position, &key_event, ctx, self.legacy))
+            .map(E::from_position)
+    }
+
+    /// Dispatch events from the key bindings of a shared key map, the iterator keeps the key map
+    /// alive even if it is replaced in the meantime
+    pub fn dispatch_shared(self: Arc<Self>, key_event: &KeyEvent) -> impl Iterator<Item = E> {
+        let key_event = *key_event;
+        let mut next = 0;
+        std::iter::from_fn(move || loop {
+            let position = *self.index.get(&key_event).get(next)?;
+            next += 1;
+            if holds(&self.key_bindings, position, &key_event, &(), self.legacy) {
+                return Some(E::from_position(position));
+            }
+        })
+    }
+
+    /// Key bindings display of the event
+    pub fn key_bindings_display(&self, event: &E) -> String {
+        format!("{}", self.key_bindings(event))
+    }
+
+    /// Key bindings display of the event with format
+    pub fn key_bindings_display_with_format(&self, event: &E, f: &DisplayFormat) -> String {
+        let key_bindings = self.key_bindings(event);
+        match f {
+            DisplayFormat::Symbols => format!("{}", key_bindings),
+            DisplayFormat::Debug => format!("{:?}", key_bindings),
+            _f => key_bindings.display(_f),
+        }
+    }
+
+    /// A matcher for key sequences with more than one key stroke, e.g. `"g g"`, which takes
+    /// key events one by one. The matcher owns a copy of the key bindings.
+    pub fn sequence_matcher(&self) -> KeySequenceMatcher<E> {
+        let mut matcher = KeySequenceMatcher::new();
+        matcher.set_timeout(self.sequence_timeout);
+        matcher.set_legacy_mode(self.legacy);
+        for (position, kbs) in self.key_bindings.iter().enumerate() {
+            matcher.add(E::from_position(position), kbs.clone());
+        }
+        matcher
+    }
+
+    /// A matcher for key sequences in the mode and in all modes, see
+    /// [`KeyMap::sequence_matcher`]
+    pub fn sequence_matcher_in(&self, mode: &str) -> KeySequenceMatcher<E> {
+        let mut matcher = KeySequenceMatcher::new();
+        matcher.set_timeout(self.sequence_timeout);
+        matcher.set_legacy_mode(self.legacy);
+        for (position, kbs) in self.key_bindings.iter().enumerate() {
+            let mut kbs = kbs.clone();
+            if let Some(m) = self.mode(mode) {
+                for seq in m.key_bindings[position].iter() {
+                    kbs.push(seq.clone());
+                }
+            }
+            matcher.add(E::from_position(position), kbs);
+        }
+        matcher
+    }
+
+    /// The keys claimed by more than one event, and the keys shadowing the key sequences starting
+    /// with them, see [`crate::KeyBindTrait::conflicts`]
+    pub fn conflicts(&self) -> Vec<KeyConflict<E>> {
+        let defaults = self.defaults.as_deref();
+        let global = claims(
+            &self.key_bindings,
+            defaults.map(|d| d.key_bindings.as_slice()),
+            false,
+            &self.overlap_allowed,
+        );
+        let mut conflicts = find_conflicts(None, &global);
+        for mode in self.modes.iter() {
+            let mode_defaults = defaults.map(|d| {
+                d.mode(mode.name)
+                    .map_or(&[][..], |m| m.key_bindings.as_slice())
+            });
+            let mut claims = claims(&mode.key_bindings, mode_defaults, true, &self.overlap_allowed);
+            claims.extend(global.iter().cloned());
+            conflicts.extend(find_conflicts(Some(mode.name), &claims));
+        }
+        conflicts
+    }
+
+    /// The key bindings which can not be distinguished under the current terminal mode, see
+    /// [`crate::KeyBindTrait::legacy_ambiguities`]
+    pub fn legacy_ambiguities(&self) -> Vec<LegacyAmbiguity<E>> {
+        if !self.legacy {
+            return Vec::new();
+        }
+        self.key_bindings
+            .iter()
+            .enumerate()
+            .flat_map(|(position, kbs)| {
+                kbs.legacy_ambiguities()
+                    .into_iter()
+                    .map(move |(key_binding, reported_as)| LegacyAmbiguity {
+                        action: E::from_position(position),
+                        key_binding,
+                        reported_as,
+                    })
+            })
+            .collect()
+    }
+}
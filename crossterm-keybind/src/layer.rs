@@ -0,0 +1,67 @@
+use crate::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// A layer of the key bind config, the layers are applied in order on top of the default key
+/// bindings, see [`crate::KeyBindTrait::load_keymap_layers`]
+///
+/// The layer changing the key bindings of an event last is recorded, e.g. to show
+/// "(customized in ~/.config/myapp/keys.toml)" in the help screen with [`crate::KeyMap::layer_of`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConfigLayer {
+    /// A config file, e.g. `/etc/myapp/keys.toml`, the layer is skipped if the file does not exist
+    File(PathBuf),
+    /// A config in TOML, e.g. from the settings of the app, the name is shown as the provenance
+    Toml { name: String, contents: String },
+}
+
+impl ConfigLayer {
+    /// A layer of a config file, skipped if the file does not exist, the leading `~` of the path
+    /// is expanded to the home directory
+    pub fn file(path: impl AsRef<Path>) -> Self {
+        ConfigLayer::File(crate::expand_tilde(path))
+    }
+
+    /// A layer of a pre-parsed, serializable value (e.g. a `toml::Table`) with a name to show as
+    /// the provenance
+    #[cfg(feature = "derive")]
+    pub fn table<T: crate::serde::Serialize>(name: impl Into<String>, table: &T) -> Result<Self, Error> {
+        let contents = toml::to_string(table).map_err(|e| Error::LoadConfigError(e.to_string()))?;
+        Ok(ConfigLayer::Toml {
+            name: name.into(),
+            contents,
+        })
+    }
+
+    /// The modification time of the config file, used to record it before reading
+    #[doc(hidden)]
+    pub fn modified(&self) -> Option<SystemTime> {
+        match self {
+            ConfigLayer::File(path) => crate::keymap::modified(path),
+            ConfigLayer::Toml { .. } => None,
+        }
+    }
+
+    /// The contents of the layer, `None` if the config file does not exist
+    #[doc(hidden)]
+    pub fn read(&self) -> Result<Option<String>, Error> {
+        match self {
+            ConfigLayer::File(path) => match std::fs::read_to_string(path) {
+                Ok(contents) => Ok(Some(contents)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(Error::ReadConfigError(e)),
+            },
+            ConfigLayer::Toml { contents, .. } => Ok(Some(contents.clone())),
+        }
+    }
+}
+
+impl fmt::Display for ConfigLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigLayer::File(path) => write!(f, "{}", path.display()),
+            ConfigLayer::Toml { name, .. } => write!(f, "{name}"),
+        }
+    }
+}
@@ -13,7 +13,20 @@
 //! to generate the the keyconfig in a supper easy way, you can have a toml key config for your
 //! events and allow user to patch part of it.
 
+#[cfg(feature = "derive")]
+mod alias;
+mod config_dir;
+mod conflict;
+#[cfg(feature = "derive")]
+mod deserialize;
 mod error;
+#[cfg(any(feature = "json_example", feature = "yaml_example"))]
+mod example;
+mod keymap;
+mod layer;
+#[cfg(feature = "derive")]
+mod patch;
+mod stack;
 mod traits;
 
 #[cfg(feature = "crossterm_0_29_0")]
@@ -21,8 +34,22 @@ pub use crossterm_0_29_0::event;
 #[cfg(feature = "crossterm_0_28_1")]
 pub use crossterm_0_28_1::event;
 #[cfg(feature = "derive")]
+#[doc(hidden)]
+pub use alias::{migrate_aliases, migrate_config_file};
+#[cfg(feature = "derive")]
 pub use crossterm_keybind_derive::KeyBind;
+pub use config_dir::{config_file_path, expand_tilde};
+pub use conflict::{ConflictKind, KeyClaim, KeyConflict, LegacyAmbiguity, Origin};
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub use deserialize::deserialize_patch;
 pub use error::Error;
+pub use keymap::{KeyMap, KeyMapEvent};
+pub use layer::ConfigLayer;
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub use patch::apply_list_patches;
+pub use stack::KeymapStack;
 #[cfg(feature = "derive")]
 pub use struct_patch;
 #[cfg(feature = "derive")]
@@ -34,5 +61,10 @@ pub use log;
 #[cfg(feature = "derive")]
 pub use serde;
 pub use traits::KeyBindTrait;
+#[doc(hidden)]
+pub use traits::KeyMapSource;
 
-pub use crossterm_keybind_core::{KeyBinding, KeyBindings, DisplayFormat};
+pub use crossterm_keybind_core::{
+    DigitKeyBindings, DisplayFormat, KeyBinding, KeyBindingIndex, KeyBindings,
+    KeyContext, KeySequence, KeySequenceMatcher, ParseKeyBindingError, SequenceMatch, When,
+};
@@ -0,0 +1,117 @@
+use crate::{Error, KeySequence};
+
+/// The key of a key binding in the config, e.g. `"q"` or `{ key = "Enter", when = "listFocused" }`
+fn key_of(value: &toml::Value) -> Option<&str> {
+    match value {
+        toml::Value::String(key) => Some(key),
+        toml::Value::Table(t) => t.get("key").and_then(|k| k.as_str()),
+        _ => None,
+    }
+}
+
+/// The key bindings are the same, e.g. `"Ctrl+q"` and `"Control+q"`, the ones can not be parsed,
+/// e.g. `"Alt+<digit>"`, are compared as text
+fn same_key(a: &toml::Value, b: &toml::Value) -> bool {
+    let (Some(a), Some(b)) = (key_of(a), key_of(b)) else {
+        return false;
+    };
+    match (KeySequence::parse(a), KeySequence::parse(b)) {
+        (Ok(a), Ok(b)) => a.overlaps(&b),
+        _ => a == b,
+    }
+}
+
+/// The key bindings of a patch item, e.g. `add` of `quit = { add = ["Control+q"] }`
+fn items(action: &str, patch: &toml::Table, name: &str) -> Result<Vec<toml::Value>, Error> {
+    match patch.get(name) {
+        None => Ok(Vec::new()),
+        Some(toml::Value::Array(items)) => Ok(items.clone()),
+        Some(_) => Err(Error::LoadConfigError(format!(
+            "`{action}.{name}` should be a list of key bindings"
+        ))),
+    }
+}
+
+/// Replace the patches of the key bindings in a table with the lists computed from the current
+/// key bindings, which are patched by the layers below, the prefix is the table of the mode, e.g.
+/// `normal.`
+///
+/// Without inheriting the defaults, the patches of the key bindings still the defaults are computed
+/// against empty lists, and these key bindings absent in the table are unbound, while the ones
+/// changed by the layers below are kept.
+fn apply(
+    table: &mut toml::Table,
+    current: &toml::Table,
+    defaults: Option<&toml::Table>,
+    prefix: &str,
+    inherit_defaults: bool,
+) -> Result<(), Error> {
+    let is_default = |action: &str| defaults.and_then(|d| d.get(action)) == current.get(action);
+    for (action, value) in table.iter_mut() {
+        let (Some(patch), Some(current_kbs)) = (
+            value.as_table(),
+            current.get(action).and_then(|d| d.as_array()),
+        ) else {
+            continue;
+        };
+        let mut kbs = if inherit_defaults || !is_default(action) {
+            current_kbs.clone()
+        } else {
+            Vec::new()
+        };
+        let action = format!("{prefix}{action}");
+        if let Some(field) = patch.keys().find(|k| *k != "add" && *k != "remove") {
+            return Err(Error::LoadConfigError(format!(
+                "unknown field `{field}` of `{action}`, please patch the key bindings with `add` and `remove`"
+            )));
+        }
+        for removed in items(&action, patch, "remove")? {
+            let len = kbs.len();
+            kbs.retain(|kb| !same_key(kb, &removed));
+            if kbs.len() == len {
+                log::warn!("`{action}` does not have the key binding {removed} to remove in the keybind config");
+            }
+        }
+        for added in items(&action, patch, "add")? {
+            if !kbs.iter().any(|kb| same_key(kb, &added)) {
+                kbs.push(added);
+            }
+        }
+        *value = toml::Value::Array(kbs);
+    }
+    if !inherit_defaults {
+        for (action, _) in current.iter().filter(|(_, c)| c.is_array()) {
+            if !table.contains_key(action) && is_default(action) {
+                table.insert(action.clone(), toml::Value::Array(Vec::new()));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Compute the key bindings patched with `add` and `remove` against the current key bindings,
+/// e.g. `quit = { add = ["Control+q"], remove = ["Q"] }`, in the top level table and the tables
+/// of modes. With `inherit_defaults = false`, every key binding not changed by the layers below
+/// starts from empty instead of the defaults.
+#[doc(hidden)]
+pub fn apply_list_patches(table: &mut toml::Table, current: &toml::Table, defaults: &toml::Table) -> Result<(), Error> {
+    let inherit_defaults = table
+        .get("inherit_defaults")
+        .and_then(|i| i.as_bool())
+        .unwrap_or(true);
+    apply(table, current, Some(defaults), "", inherit_defaults)?;
+    for (mode, mode_current) in current.iter() {
+        let Some(mode_current) = mode_current.as_table() else {
+            continue;
+        };
+        if !inherit_defaults && !table.contains_key(mode) {
+            table.insert(mode.clone(), toml::Value::Table(toml::Table::new()));
+        }
+        let Some(mode_table) = table.get_mut(mode).and_then(|t| t.as_table_mut()) else {
+            continue;
+        };
+        let mode_defaults = defaults.get(mode).and_then(|d| d.as_table());
+        apply(mode_table, mode_current, mode_defaults, &format!("{mode}."), inherit_defaults)?;
+    }
+    Ok(())
+}
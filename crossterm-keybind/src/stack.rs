@@ -0,0 +1,169 @@
+use crate::event::KeyEvent;
+use crate::{KeyContext, KeyMap, KeyMapEvent};
+use std::sync::Arc;
+
+/// A key map of the events `E` in [`KeymapStack`], with the mapping of the events into the common
+/// action type `A`
+trait Layer<A>: Send + Sync {
+    /// Push the actions dispatched from the key event, return `true` if any is pushed
+    fn dispatch(&self, key_event: &KeyEvent, mode: Option<&str>, ctx: &dyn KeyContext, actions: &mut Vec<A>) -> bool;
+}
+
+struct KeyMapLayer<E, F> {
+    keymap: Arc<KeyMap<E>>,
+    map: F,
+}
+
+impl<E, A, F> Layer<A> for KeyMapLayer<E, F>
+where
+    E: KeyMapEvent,
+    F: Fn(E) -> A + Send + Sync,
+{
+    fn dispatch(&self, key_event: &KeyEvent, mode: Option<&str>, ctx: &dyn KeyContext, actions: &mut Vec<A>) -> bool {
+        let len = actions.len();
+        match mode {
+            Some(mode) => actions.extend(
+                self.keymap
+                    .dispatch_in_iter_with(mode, key_event, ctx)
+                    .map(&self.map),
+            ),
+            None => actions.extend(self.keymap.dispatch_iter_with(key_event, ctx).map(&self.map)),
+        }
+        actions.len() > len
+    }
+}
+
+/// A layer of [`KeymapStack`]
+struct StackLayer<A> {
+    layer: Box<dyn Layer<A>>,
+    opaque: bool,
+}
+
+/// A stack of key binding layers, e.g. a dialog over a panel over the global key bindings.
+///
+/// A key event is dispatched to the topmost layer binding it, so the keys unbound in a layer fall
+/// through to the layers below, unless the layer is opaque, e.g. a modal dialog swallowing all keys.
+///
+/// Each layer is a [`KeyMap`] of a [`crate::KeyBindTrait`] implementor, owned or shared, e.g. the
+/// global one from [`crate::KeyBindTrait::keymap`], and the events of each layer are mapped into
+/// the common action type `A`, e.g. a variant of an enum wrapping the events of the layers.
+///
+/// ```ignore
+/// enum Action {
+///     Global(GlobalKeyBinding),
+///     Dialog(DialogKeyBinding),
+/// }
+///
+/// let mut stack = KeymapStack::new();
+/// stack.push(GlobalKeyBinding::keymap().unwrap(), Action::Global);
+/// stack.push_opaque(DialogKeyBinding::load_keymap_file(path)?, Action::Dialog);
+/// for action in stack.dispatch_in_with("normal", &key, &ctx) {
+///     // ...
+/// }
+/// ```
+pub struct KeymapStack<A> {
+    layers: Vec<StackLayer<A>>,
+}
+
+impl<A> Default for KeymapStack<A> {
+    fn default() -> Self {
+        Self { layers: Vec::new() }
+    }
+}
+
+impl<A> KeymapStack<A> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn push_layer<E, F>(&mut self, keymap: impl Into<Arc<KeyMap<E>>>, map: F, opaque: bool)
+    where
+        E: KeyMapEvent + 'static,
+        F: Fn(E) -> A + Send + Sync + 'static,
+    {
+        self.layers.push(StackLayer {
+            layer: Box::new(KeyMapLayer {
+                keymap: keymap.into(),
+                map,
+            }),
+            opaque,
+        });
+    }
+
+    /// Push a layer of the key map of the events `E` on the top, the keys unbound in it fall
+    /// through.
+    ///
+    /// The layer holds the key map, e.g. the one from [`crate::KeyBindTrait::keymap`] keeps
+    /// dispatching with the old key bindings after [`crate::KeyBindTrait::set_keymap`] or
+    /// [`crate::KeyBindTrait::reload`], push the new key map again to pick up the changes.
+    pub fn push<E, F>(&mut self, keymap: impl Into<Arc<KeyMap<E>>>, map: F)
+    where
+        E: KeyMapEvent + 'static,
+        F: Fn(E) -> A + Send + Sync + 'static,
+    {
+        self.push_layer(keymap, map, false);
+    }
+
+    /// Push an opaque layer of the key map of the events `E` on the top, the keys unbound in it
+    /// are swallowed, the layer holds the key map as [`KeymapStack::push`]
+    pub fn push_opaque<E, F>(&mut self, keymap: impl Into<Arc<KeyMap<E>>>, map: F)
+    where
+        E: KeyMapEvent + 'static,
+        F: Fn(E) -> A + Send + Sync + 'static,
+    {
+        self.push_layer(keymap, map, true);
+    }
+
+    /// Remove the topmost layer, return `false` if the stack is empty
+    pub fn pop(&mut self) -> bool {
+        self.layers.pop().is_some()
+    }
+
+    /// The number of layers
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Remove all the layers
+    pub fn clear(&mut self) {
+        self.layers.clear();
+    }
+
+    /// Dispatch the key event to the topmost layer binding it, the result is empty if no layer
+    /// binds it or an opaque layer above swallows it
+    pub fn dispatch(&self, key_event: &KeyEvent) -> Vec<A> {
+        self.dispatch_layers(key_event, None, &())
+    }
+
+    /// Dispatch the key event to the topmost layer binding it with the key bindings of which the
+    /// conditions hold in the context
+    pub fn dispatch_with(&self, key_event: &KeyEvent, ctx: &dyn KeyContext) -> Vec<A> {
+        self.dispatch_layers(key_event, None, ctx)
+    }
+
+    /// Dispatch the key event to the topmost layer binding it in the mode or in all modes, a
+    /// layer without the mode only dispatches with its key bindings in all modes
+    pub fn dispatch_in(&self, mode: &str, key_event: &KeyEvent) -> Vec<A> {
+        self.dispatch_layers(key_event, Some(mode), &())
+    }
+
+    /// Dispatch the key event to the topmost layer binding it in the mode or in all modes, with
+    /// the key bindings of which the conditions hold in the context
+    pub fn dispatch_in_with(&self, mode: &str, key_event: &KeyEvent, ctx: &dyn KeyContext) -> Vec<A> {
+        self.dispatch_layers(key_event, Some(mode), ctx)
+    }
+
+    fn dispatch_layers(&self, key_event: &KeyEvent, mode: Option<&str>, ctx: &dyn KeyContext) -> Vec<A> {
+        let mut actions = Vec::new();
+        for StackLayer { layer, opaque } in self.layers.iter().rev() {
+            if layer.dispatch(key_event, mode, ctx, &mut actions) || *opaque {
+                break;
+            }
+        }
+        actions
+    }
+}
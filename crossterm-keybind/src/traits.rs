@@ -1,3 +1,69 @@
+use crate::{Error, KeyMap, KeyMapEvent};
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// The storage and the loading of the key map of the events, implemented by the derive macro, on
+/// which the default methods of [`KeyBindTrait`] are built.
+#[doc(hidden)]
+pub trait KeyMapSource: KeyMapEvent + 'static {
+    /// The deprecated names of the events and their current names
+    const ALIASES: &'static [(&'static str, &'static str)];
+
+    /// The global key map of the events
+    fn keymap_slot() -> &'static RwLock<Option<Arc<KeyMap<Self>>>>;
+
+    /// Load the key map patched with the configs in TOML in order, the ones which do not exist are
+    /// skipped
+    fn load_patches(patches: &[Option<&str>]) -> Result<KeyMap<Self>, Error>;
+
+    /// Load the key map patched with a parsed config
+    #[cfg(feature = "derive")]
+    fn load_table(patch: toml::Table) -> Result<KeyMap<Self>, Error>;
+}
+
+/// The global key map, which must be initialized ahead
+#[cfg(feature = "safety")]
+fn initialized<E: KeyMapSource>() -> Option<Arc<KeyMap<E>>> {
+    let keymap = E::keymap_slot().read().unwrap_or_else(PoisonError::into_inner).clone();
+    if keymap.is_none() {
+        // NOTE
+        // You are using crossterm in an unexpected way, we prevent panic in runtime,
+        // please run anyfunction after `init_and_load`/`init_and_load_file`
+        log::warn!("Keybindings are used without initialization, it will never match");
+    }
+    keymap
+}
+
+/// The global key map, which must be initialized ahead
+#[cfg(not(feature = "safety"))]
+fn initialized<E: KeyMapSource>() -> Option<Arc<KeyMap<E>>> {
+    Some(
+        E::keymap_slot()
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+            .expect("Keybindings are used without initialization, please run `init_and_load`/`init_and_load_file` ahead"),
+    )
+}
+
+/// The global key map can only be initialized once
+#[cfg(feature = "safety")]
+fn check_init<E: KeyMapSource>() -> Result<(), Error> {
+    if E::keymap_slot().read().unwrap_or_else(PoisonError::into_inner).is_some() {
+        return Err(Error::ConfigDoubleInitError);
+    }
+    Ok(())
+}
+
+/// The global key map can be initialized again without the `safety` feature
+#[cfg(not(feature = "safety"))]
+fn check_init<E: KeyMapSource>() -> Result<(), Error> {
+    Ok(())
+}
+
+/// The key bindings of the events, implemented by the derive macro `KeyBind`.
+///
+/// A manual implementation only needs the methods without default bodies, the ones with default
+/// bodies are built on the key map of the derive macro.
 pub trait KeyBindTrait {
     /// Initialize a default key bind config and optionally patch it from a pre-parsed,
     /// serializable value (e.g. a `toml::Table`).
@@ -10,13 +76,207 @@ pub trait KeyBindTrait {
     #[cfg(feature = "derive")]
     fn init_and_load<T: crate::serde::Serialize>(patch_table: Option<T>) -> Result<(), crate::Error>;
 
-    /// Initialize a default key bind config and optionally load a config from the path then patch it
+    /// Initialize a default key bind config and patch it from a deserializer of any format, e.g.
+    /// a JSON or YAML settings file, or a `serde_json::Value`.
+    ///
+    /// The patch is deserialized directly, a null keeps the default key bindings, e.g.
+    /// `"quit": null`, and the keys which are not strings are taken as text.
+    ///
+    /// Please note, this will be the first method you need to call before using `match_any`,
+    /// `dispatch`, `key_bindings_display` or `key_bindings_display_with_format`.
+    #[cfg(feature = "derive")]
+    fn init_and_load_from<'de, D: crate::serde::Deserializer<'de>>(deserializer: D) -> Result<(), crate::Error>
+    where
+        Self: KeyMapSource,
+    {
+        check_init::<Self>()?;
+        Self::set_keymap(Self::load_keymap_from(deserializer)?);
+        Ok(())
+    }
+
+    /// Initialize a default key bind config and optionally load a config from the path then patch it,
+    /// the leading `~` of the path is expanded to the home directory
     ///
     /// Please note, this will be the first method you need to call before using `match_any`,
     /// `dispatch`, `key_bindings_display` or `key_bindings_display_with_format`, such that all the
     /// keybind from the user can be initialized.
     fn init_and_load_file(patch_path: Option<std::path::PathBuf>) -> Result<(), crate::Error>;
 
+    /// Initialize a default key bind config and patch it with the layers in order, e.g. a system
+    /// wide file, the file of the user and the file of the project, see `load_keymap_layers`.
+    ///
+    /// Please note, this will be the first method you need to call before using `match_any`,
+    /// `dispatch`, `key_bindings_display` or `key_bindings_display_with_format`.
+    fn init_and_load_layers(layers: Vec<crate::ConfigLayer>) -> Result<(), crate::Error>
+    where
+        Self: KeyMapSource,
+    {
+        check_init::<Self>()?;
+        Self::set_keymap(Self::load_keymap_layers(layers)?);
+        Ok(())
+    }
+
+    /// Initialize a default key bind config and patch it from the config file of the app, e.g.
+    /// `init_from_config_dir("myapp", "keybinds.toml")` loads
+    /// `$XDG_CONFIG_HOME/myapp/keybinds.toml`, or `$HOME/.config/myapp/keybinds.toml`, and
+    /// returns the path of the file.
+    ///
+    /// A missing file is not an error, the default key bindings are used, and the file is loaded
+    /// by `reload_if_changed` once it is created.
+    ///
+    /// Please note, this will be the first method you need to call before using `match_any`,
+    /// `dispatch`, `key_bindings_display` or `key_bindings_display_with_format`.
+    fn init_from_config_dir(app_name: &str, file_name: &str) -> Result<std::path::PathBuf, crate::Error>
+    where
+        Self: KeyMapSource,
+    {
+        let path = crate::config_file_path(app_name, file_name).ok_or(crate::Error::ConfigDirNotFoundError)?;
+        Self::init_and_load_layers(vec![crate::ConfigLayer::file(&path)])?;
+        Ok(path)
+    }
+
+    /// Load an owned key map from the default key bind config, optionally patched from a
+    /// pre-parsed, serializable value (e.g. a `toml::Table`), see `init_and_load`.
+    ///
+    /// The key map is independent of the global key bindings, it can be used directly or set as
+    /// the global one with `set_keymap`.
+    #[cfg(feature = "derive")]
+    fn load_keymap<T: crate::serde::Serialize>(
+        patch_table: Option<T>,
+    ) -> Result<crate::KeyMap<Self>, crate::Error>
+    where
+        Self: KeyMapSource,
+    {
+        if let Some(table) = patch_table {
+            Self::load_table(toml::Table::try_from(table).map_err(|e| Error::LoadConfigError(e.to_string()))?)
+        } else {
+            Self::load_patches(&[])
+        }
+    }
+
+    /// Load an owned key map from the default key bind config patched from a deserializer of any
+    /// format, see `init_and_load_from`.
+    #[cfg(feature = "derive")]
+    fn load_keymap_from<'de, D: crate::serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<crate::KeyMap<Self>, crate::Error>
+    where
+        Self: KeyMapSource,
+    {
+        match crate::deserialize_patch(deserializer)? {
+            Some(table) => Self::load_table(table),
+            None => Self::load_patches(&[]),
+        }
+    }
+
+    /// Load an owned key map from the default key bind config, optionally patched from a config
+    /// file, see `init_and_load_file`.
+    fn load_keymap_file(
+        patch_path: Option<std::path::PathBuf>,
+    ) -> Result<crate::KeyMap<Self>, crate::Error>
+    where
+        Self: KeyMapSource,
+    {
+        let Some(path) = patch_path else {
+            return Self::load_patches(&[]);
+        };
+        let path = crate::expand_tilde(path);
+        let modified = KeyMap::<Self>::modified(&path);
+        let contents = std::fs::read_to_string(&path).map_err(Error::ReadConfigError)?;
+        Ok(Self::load_patches(&[Some(&contents)])?.with_file(path, modified))
+    }
+
+    /// Load an owned key map from the default key bind config, patched with the layers in order,
+    /// so a later layer overrides the earlier ones, and `add`/`remove` patches of a layer are
+    /// computed against the layers below it. The config files which do not exist are skipped.
+    ///
+    /// The layer changing the key bindings of each event last is recorded, see
+    /// [`crate::KeyMap::layer_of`].
+    fn load_keymap_layers(layers: Vec<crate::ConfigLayer>) -> Result<crate::KeyMap<Self>, crate::Error>
+    where
+        Self: KeyMapSource,
+    {
+        let mut loaded = Vec::with_capacity(layers.len());
+        let mut contents = Vec::with_capacity(layers.len());
+        for layer in layers {
+            let modified = layer.modified();
+            contents.push(layer.read()?);
+            loaded.push((layer, modified));
+        }
+        let patches: Vec<Option<&str>> = contents.iter().map(Option::as_deref).collect();
+        Ok(Self::load_patches(&patches)?.with_layers(loaded))
+    }
+
+    /// The global key map initialized by `init_and_load`/`init_and_load_file` or `set_keymap`,
+    /// `None` before initialization.
+    fn keymap() -> Option<std::sync::Arc<crate::KeyMap<Self>>>
+    where
+        Self: KeyMapSource,
+    {
+        Self::keymap_slot().read().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    /// Replace the global key map, e.g. to apply the changes of the settings at runtime.
+    ///
+    /// The methods of the global key bindings in progress keep using the previous key map, and the
+    /// following calls use the new one.
+    fn set_keymap(keymap: crate::KeyMap<Self>)
+    where
+        Self: KeyMapSource,
+    {
+        *Self::keymap_slot().write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(keymap));
+    }
+
+    /// Load the config file, or the layers, of the global key map again and replace the global
+    /// key map.
+    ///
+    /// If the config file can not be loaded, e.g. a broken edit, the previous key map is kept and
+    /// the error is returned. Nothing is reloaded if the global key map is not from a file.
+    fn reload() -> Result<(), crate::Error>
+    where
+        Self: KeyMapSource,
+    {
+        let keymap = Self::keymap().ok_or(crate::Error::ConfigNotInitError)?;
+        let legacy = keymap.legacy_mode();
+        if keymap.layers().next().is_some() {
+            Self::set_keymap(Self::load_keymap_layers(keymap.layers().cloned().collect())?.with_legacy_mode(legacy));
+        } else if let Some(path) = keymap.path() {
+            Self::set_keymap(Self::load_keymap_file(Some(path.to_path_buf()))?.with_legacy_mode(legacy));
+        }
+        Ok(())
+    }
+
+    /// Reload the global key map if the modification time of its config file changed, return
+    /// `true` if it is reloaded, e.g. polled from the event loop of the application.
+    ///
+    /// If the config file can not be loaded, the previous key map is kept and the error is
+    /// returned, a following call tries again.
+    fn reload_if_changed() -> Result<bool, crate::Error>
+    where
+        Self: KeyMapSource,
+    {
+        let keymap = Self::keymap().ok_or(crate::Error::ConfigNotInitError)?;
+        if keymap.is_file_changed() {
+            Self::reload()?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Rename the deprecated names of events in the config file to the current ones, e.g. the
+    /// old name of `#[keybind(alias = "toggle_help_widget")]`, keeping the comments and the
+    /// layout of the file. Return `true` if the file is rewritten.
+    ///
+    /// The deprecated names are accepted when loading anyway, with a warning in the log.
+    #[cfg(feature = "derive")]
+    fn migrate_config_file(path: &std::path::Path) -> Result<bool, crate::Error>
+    where
+        Self: KeyMapSource,
+    {
+        crate::migrate_config_file(path, Self::ALIASES)
+    }
+
     /// Key event match for the key bindings
     ///
     /// Please note, this method requires `init_and_load_file` to run ahead.
@@ -28,6 +288,30 @@ pub trait KeyBindTrait {
     /// Export a file with key config example for events
     fn to_toml_example<P: AsRef<std::path::Path>>(file_name: P) -> std::io::Result<()>;
 
+    /// Key config example for events in JSON, without the documentation of the TOML one
+    #[cfg(feature = "json_example")]
+    fn json_example() -> Result<String, crate::Error> {
+        crate::example::json_example(&Self::toml_example())
+    }
+
+    /// Export a file with key config example for events in JSON
+    #[cfg(feature = "json_example")]
+    fn to_json_example<P: AsRef<std::path::Path>>(file_name: P) -> std::io::Result<()> {
+        std::fs::write(file_name, Self::json_example().map_err(std::io::Error::other)?)
+    }
+
+    /// Key config example for events in YAML, without the documentation of the TOML one
+    #[cfg(feature = "yaml_example")]
+    fn yaml_example() -> Result<String, crate::Error> {
+        crate::example::yaml_example(&Self::toml_example())
+    }
+
+    /// Export a file with key config example for events in YAML
+    #[cfg(feature = "yaml_example")]
+    fn to_yaml_example<P: AsRef<std::path::Path>>(file_name: P) -> std::io::Result<()> {
+        std::fs::write(file_name, Self::yaml_example().map_err(std::io::Error::other)?)
+    }
+
     /// Key bindings display
     ///
     /// Please note, this method requires `init_and_load`/`init_and_load_file` to run ahead.
@@ -44,4 +328,116 @@ pub trait KeyBindTrait {
     fn dispatch(key_event: &crate::event::KeyEvent) -> Vec<Self>
     where
         Self: Sized;
+
+    /// Dispatch events from the key bindings without allocation, the key event is looked up in
+    /// an index built when the key bindings are loaded.
+    ///
+    /// Please note, this method requires `init_and_load`/`init_and_load_file` to run ahead.
+    fn dispatch_iter(key_event: &crate::event::KeyEvent) -> impl Iterator<Item = Self>
+    where
+        Self: KeyMapSource,
+    {
+        initialized::<Self>()
+            .into_iter()
+            .flat_map(|keymap| keymap.dispatch_shared(key_event))
+    }
+
+    /// Key event match for the key bindings in the mode, e.g. `#[keybindings(mode = "normal", ["j"])]`,
+    /// and the key bindings in all modes
+    ///
+    /// Please note, this method requires `init_and_load`/`init_and_load_file` to run ahead.
+    fn match_any_in(&self, mode: &str, key_event: &crate::event::KeyEvent) -> bool
+    where
+        Self: KeyMapSource,
+    {
+        initialized::<Self>().is_some_and(|keymap| keymap.match_any_in(mode, self, key_event))
+    }
+
+    /// Dispatch events from the key bindings in the mode, then the ones in all modes
+    ///
+    /// Please note, this method requires `init_and_load`/`init_and_load_file` to run ahead.
+    fn dispatch_in(mode: &str, key_event: &crate::event::KeyEvent) -> Vec<Self>
+    where
+        Self: KeyMapSource,
+    {
+        initialized::<Self>().map_or_else(Vec::new, |keymap| keymap.dispatch_in(mode, key_event))
+    }
+
+    /// Key event match for the key bindings of which the `when` conditions hold in the context,
+    /// e.g. `{ key = "Enter", when = "listFocused && !readOnly" }`.
+    /// The other methods match the key bindings in the empty context, where no flag is set.
+    ///
+    /// Please note, this method requires `init_and_load`/`init_and_load_file` to run ahead.
+    fn match_any_with(
+        &self,
+        key_event: &crate::event::KeyEvent,
+        ctx: &dyn crate::KeyContext,
+    ) -> bool
+    where
+        Self: KeyMapSource,
+    {
+        initialized::<Self>().is_some_and(|keymap| keymap.match_any_with(self, key_event, ctx))
+    }
+
+    /// Dispatch events from the key bindings of which the `when` conditions hold in the context
+    ///
+    /// Please note, this method requires `init_and_load`/`init_and_load_file` to run ahead.
+    fn dispatch_with(key_event: &crate::event::KeyEvent, ctx: &dyn crate::KeyContext) -> Vec<Self>
+    where
+        Self: KeyMapSource,
+    {
+        initialized::<Self>().map_or_else(Vec::new, |keymap| keymap.dispatch_with(key_event, ctx))
+    }
+
+    /// Dispatch events from the key bindings in the mode, then the ones in all modes, of which the
+    /// `when` conditions hold in the context
+    ///
+    /// Please note, this method requires `init_and_load`/`init_and_load_file` to run ahead.
+    fn dispatch_in_with(
+        mode: &str,
+        key_event: &crate::event::KeyEvent,
+        ctx: &dyn crate::KeyContext,
+    ) -> Vec<Self>
+    where
+        Self: KeyMapSource,
+    {
+        initialized::<Self>().map_or_else(Vec::new, |keymap| keymap.dispatch_in_with(mode, key_event, ctx))
+    }
+
+    /// A matcher for key sequences with more than one key stroke, e.g. `"g g"`, which takes
+    /// key events one by one. The matcher owns a copy of the current key bindings.
+    ///
+    /// Please note, this method requires `init_and_load`/`init_and_load_file` to run ahead.
+    fn sequence_matcher() -> crate::KeySequenceMatcher<Self>
+    where
+        Self: KeyMapSource,
+    {
+        initialized::<Self>().map_or_else(crate::KeySequenceMatcher::new, |keymap| keymap.sequence_matcher())
+    }
+
+    /// The key bindings which can not be distinguished under the current terminal mode, e.g.
+    /// `"Control+i"` is reported as `Tab` by legacy terminals.
+    /// The result is always empty unless the global key map is in legacy mode, see
+    /// [`crate::KeyMap::with_legacy_mode`].
+    ///
+    /// Please note, this method requires `init_and_load`/`init_and_load_file` to run ahead.
+    fn legacy_ambiguities() -> Vec<crate::LegacyAmbiguity<Self>>
+    where
+        Self: KeyMapSource,
+    {
+        initialized::<Self>().map_or_else(Vec::new, |keymap| keymap.legacy_ambiguities())
+    }
+
+    /// The keys claimed by more than one event, and the keys shadowing the key sequences starting
+    /// with them, e.g. `"g"` and `"g g"`, after the patch of the user is applied.
+    /// Each claim tells whether the key binding is from the defaults or from the user, so the
+    /// app can warn about the conflicts or refuse to start.
+    ///
+    /// Please note, this method requires `init_and_load`/`init_and_load_file` to run ahead.
+    fn conflicts() -> Vec<crate::KeyConflict<Self>>
+    where
+        Self: KeyMapSource,
+    {
+        initialized::<Self>().map_or_else(Vec::new, |keymap| keymap.conflicts())
+    }
 }
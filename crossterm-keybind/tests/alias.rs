@@ -0,0 +1,94 @@
+use crossterm_keybind::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm_keybind::{KeyBind, KeyBindTrait};
+
+/// The deprecated names of renamed variants should still patch the key bindings
+#[test]
+fn load_deprecated_names() {
+    #[derive(KeyBind, Debug, PartialEq)]
+    enum TestKeyBindings {
+        #[keybindings["F1"]]
+        #[keybind(alias = "toggle_help_widget")]
+        ToggleHelp,
+        #[keybindings("Alt+1" => SwitchTab(1))]
+        #[keybind(alias = "tab")]
+        SwitchTab(u8),
+        #[keybindings(mode = "normal", ["j"])]
+        #[keybind(alias = "down")]
+        MoveDown,
+    }
+
+    let patch: toml::Table = toml::from_str(
+        r#"
+        toggle_help_widget = ["?"]
+        tab_1 = ["Control+1"]
+
+        [normal]
+        down = ["Down"]
+        "#,
+    )
+    .unwrap();
+    let keymap = TestKeyBindings::load_keymap(Some(patch)).expect("load_keymap should succeed");
+    let question = KeyEvent::new(KeyCode::Char('?'), KeyModifiers::NONE);
+    let ctrl_1 = KeyEvent::new(KeyCode::Char('1'), KeyModifiers::CONTROL);
+    let down = KeyEvent::new(KeyCode::Down, KeyModifiers::NONE);
+    assert_eq!(keymap.dispatch(&question), vec![TestKeyBindings::ToggleHelp]);
+    assert_eq!(keymap.dispatch(&ctrl_1), vec![TestKeyBindings::SwitchTab(1)]);
+    assert_eq!(keymap.dispatch_in("normal", &down), vec![TestKeyBindings::MoveDown]);
+
+    // the current name wins over the deprecated one
+    let patch: toml::Table = toml::from_str(
+        r#"
+        toggle_help = ["h"]
+        toggle_help_widget = ["?"]
+        "#,
+    )
+    .unwrap();
+    let keymap = TestKeyBindings::load_keymap(Some(patch)).expect("load_keymap should succeed");
+    assert!(keymap.dispatch(&question).is_empty());
+}
+
+/// The deprecated names should be renamed in the config file, and the comments are kept
+#[test]
+fn migrate_config_file_renames_deprecated_names() {
+    #[derive(KeyBind, Debug, PartialEq)]
+    enum TestKeyBindings {
+        #[keybindings["F1"]]
+        #[keybind(alias = "toggle_help_widget")]
+        ToggleHelp,
+        #[keybindings(mode = "normal", ["j"])]
+        #[keybind(alias = "down")]
+        MoveDown,
+    }
+
+    let path = std::env::temp_dir().join(format!("crossterm-keybind-alias-{}.toml", std::process::id()));
+    std::fs::write(
+        &path,
+        "# my help\ntoggle_help_widget = [\"?\"]\n\n[normal]\n  down = [\"Down\"] # arrow\n",
+    )
+    .unwrap();
+    assert!(TestKeyBindings::migrate_config_file(&path).expect("migrate should succeed"));
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "# my help\ntoggle_help = [\"?\"]\n\n[normal]\n  move_down = [\"Down\"] # arrow\n"
+    );
+    assert!(!TestKeyBindings::migrate_config_file(&path).expect("migrate should succeed"));
+
+    // dotted keys, quoted keys and inline tables
+    std::fs::write(
+        &path,
+        "'toggle_help_widget' = [\"?\"] # help\nnormal.down = [\"Down\"]\n",
+    )
+    .unwrap();
+    assert!(TestKeyBindings::migrate_config_file(&path).expect("migrate should succeed"));
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "toggle_help = [\"?\"] # help\nnormal.move_down = [\"Down\"]\n"
+    );
+    std::fs::write(&path, "normal = { \"down\" = [\"Down\"], move_up = [\"k\"] }\n").unwrap();
+    assert!(TestKeyBindings::migrate_config_file(&path).expect("migrate should succeed"));
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "normal = { move_down = [\"Down\"], move_up = [\"k\"] }\n"
+    );
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,52 @@
+use crossterm_keybind::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm_keybind::{KeyBind, KeyBindTrait};
+
+#[derive(KeyBind, Debug, PartialEq)]
+enum TestKeyBindings {
+    #[keybindings["q"]]
+    Quit,
+}
+
+/// The config file should be found in the config directory, a missing one is not an error, and
+/// `~` should be expanded to the home directory
+#[test]
+fn init_from_config_dir_resolves_path() {
+    let home = std::env::temp_dir().join(format!("crossterm-keybind-home-{}", std::process::id()));
+    std::fs::create_dir_all(home.join(".config/myapp")).unwrap();
+    // SAFETY: the only test of this binary, no other thread reads the environment
+    unsafe {
+        std::env::remove_var("XDG_CONFIG_HOME");
+        std::env::set_var("HOME", &home);
+    }
+    let expected = home.join(".config/myapp/keybinds.toml");
+    assert_eq!(
+        crossterm_keybind::config_file_path("myapp", "keybinds.toml"),
+        Some(expected.clone())
+    );
+    assert_eq!(crossterm_keybind::expand_tilde("~/.config/myapp/keybinds.toml"), expected);
+
+    let path = TestKeyBindings::init_from_config_dir("myapp", "keybinds.toml")
+        .expect("a missing config file should be the defaults");
+    assert_eq!(path, expected);
+    let q = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE);
+    let x = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE);
+    assert!(TestKeyBindings::Quit.match_any(&q));
+
+    std::fs::write(&path, r#"quit = ["x"]"#).unwrap();
+    let keymap = TestKeyBindings::load_keymap_file(Some("~/.config/myapp/keybinds.toml".into()))
+        .expect("load_keymap_file should expand ~");
+    assert!(keymap.match_any(&TestKeyBindings::Quit, &x));
+    assert_eq!(keymap.path(), Some(expected.as_path()));
+
+    let xdg = home.join("xdg");
+    // SAFETY: the only test of this binary, no other thread reads the environment
+    unsafe {
+        std::env::set_var("XDG_CONFIG_HOME", &xdg);
+    }
+    assert_eq!(
+        crossterm_keybind::config_file_path("myapp", "keybinds.toml"),
+        Some(xdg.join("myapp/keybinds.toml"))
+    );
+
+    std::fs::remove_dir_all(&home).unwrap();
+}
@@ -0,0 +1,99 @@
+use crossterm_keybind::{ConflictKind, KeyBind, KeyBindTrait, Origin};
+
+/// The conflicts after the patch should tell the events claiming the key and the origins
+#[test]
+fn conflicts_after_patch() {
+    #[derive(KeyBind, Debug, PartialEq)]
+    enum TestKeyBindings {
+        #[keybindings["Control+c", "q"]]
+        Quit,
+        #[keybindings["Esc"]]
+        Close,
+        #[keybindings["g g"]]
+        Top,
+        #[keybindings(mode = "normal", ["j"])]
+        MoveDown,
+    }
+
+    TestKeyBindings::init_and_load(None::<toml::Table>).expect("init_and_load should succeed");
+    assert!(TestKeyBindings::conflicts().is_empty());
+
+    let patch: toml::Table = toml::from_str(
+        r#"
+        close = ["Esc", "q"]
+
+        [normal]
+        move_down = ["j", "Control+c"]
+        "#,
+    )
+    .unwrap();
+    let keymap = TestKeyBindings::load_keymap(Some(patch)).expect("load_keymap should succeed");
+    let conflicts = keymap.conflicts();
+    assert_eq!(conflicts.len(), 2);
+
+    assert_eq!(conflicts[0].kind, ConflictKind::Duplicate);
+    assert_eq!(conflicts[0].mode, None);
+    assert_eq!(conflicts[0].key_binding.to_string(), "q");
+    assert_eq!(conflicts[0].claims[0].event, TestKeyBindings::Quit);
+    assert_eq!(conflicts[0].claims[0].origin, Origin::Default);
+    assert_eq!(conflicts[0].claims[1].event, TestKeyBindings::Close);
+    assert_eq!(conflicts[0].claims[1].origin, Origin::User);
+    assert_eq!(conflicts[0].to_string(), r#""q" is bound to Quit (default), Close (user)"#);
+
+    assert_eq!(conflicts[1].kind, ConflictKind::Duplicate);
+    assert_eq!(conflicts[1].mode, Some("normal"));
+    assert_eq!(
+        conflicts[1].to_string(),
+        r#""Control+c" is bound to MoveDown (user), Quit (default) in mode normal"#
+    );
+}
+
+/// A key which is the first key stroke of a key sequence should be reported as shadowing it
+#[test]
+fn conflicts_of_shadowed_sequences() {
+    #[derive(KeyBind, Debug, PartialEq)]
+    enum TestKeyBindings {
+        #[keybindings["g g"]]
+        Top,
+        #[keybindings["j"]]
+        MoveDown,
+    }
+
+    let patch: toml::Table = toml::from_str(r#"move_down = ["g"]"#).unwrap();
+    let keymap = TestKeyBindings::load_keymap(Some(patch)).expect("load_keymap should succeed");
+    let conflicts = keymap.conflicts();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].kind, ConflictKind::ShadowedSequence);
+    assert_eq!(conflicts[0].claims.len(), 2);
+    assert_eq!(
+        conflicts[0].to_string(),
+        r#""g" is bound to MoveDown (user), which shadows "g g" of Top (default)"#
+    );
+}
+
+/// The key bindings of the variants allowed to overlap should not be reported as conflicts
+#[test]
+fn conflicts_skip_allowed_overlaps() {
+    #[derive(KeyBind, Debug, PartialEq)]
+    enum TestKeyBindings {
+        #[keybindings["q"]]
+        Quit,
+        #[keybind(allow_overlap)]
+        #[keybindings["q"]]
+        CloseDialog,
+        #[keybindings("Alt+1" => SwitchTab(1), "Alt+2" => SwitchTab(2))]
+        SwitchTab(u8),
+    }
+
+    let keymap = TestKeyBindings::load_keymap(None::<toml::Table>).expect("load_keymap should succeed");
+    assert!(keymap.conflicts().is_empty());
+
+    let patch: toml::Table = toml::from_str(r#"switch_tab_2 = ["Alt+1"]"#).unwrap();
+    let keymap = TestKeyBindings::load_keymap(Some(patch)).expect("load_keymap should succeed");
+    let conflicts = keymap.conflicts();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(
+        conflicts[0].to_string(),
+        r#""Alternate+1" is bound to SwitchTab(1) (default), SwitchTab(2) (user)"#
+    );
+}
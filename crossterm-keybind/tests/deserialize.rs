@@ -0,0 +1,86 @@
+use crossterm_keybind::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm_keybind::{KeyBind, KeyBindTrait};
+
+#[derive(KeyBind, Debug, PartialEq)]
+enum TestKeyBindings {
+    #[keybindings["Control+c", "q"]]
+    Quit,
+    #[keybindings["F1", "?"]]
+    ToggleHelp,
+    #[keybindings(mode = "normal", ["j"])]
+    MoveDown,
+}
+
+/// A patch in JSON should be deserialized directly, with the nulls keeping the defaults
+#[test]
+fn load_keymap_from_json() {
+    let json = r#"{
+        "quit": { "add": ["Control+q"], "remove": ["q"] },
+        "toggle_help": null,
+        "normal": { "move_down": ["Down", { "key": "j", "when": "!editing" }] }
+    }"#;
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let keymap = TestKeyBindings::load_keymap_from(&mut deserializer).expect("load_keymap_from should succeed");
+    assert_eq!(
+        format!("{:?}", keymap.key_bindings(&TestKeyBindings::Quit)),
+        r#"["Control+c", "Control+q"]"#
+    );
+    assert_eq!(
+        format!("{:?}", keymap.key_bindings(&TestKeyBindings::ToggleHelp)),
+        r#"["F1", "?"]"#
+    );
+    let down = KeyEvent::new(KeyCode::Down, KeyModifiers::NONE);
+    assert_eq!(keymap.dispatch_in("normal", &down), vec![TestKeyBindings::MoveDown]);
+
+    let value: serde_json::Value = serde_json::from_str(r#"{ "quit": ["x"] }"#).unwrap();
+    TestKeyBindings::init_and_load_from(value).expect("init_and_load_from should succeed");
+    let x = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE);
+    assert!(TestKeyBindings::Quit.match_any(&x));
+
+    let keymap = TestKeyBindings::load_keymap_from(serde_json::Value::Null).expect("a null patch is the defaults");
+    let q = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE);
+    assert!(keymap.match_any(&TestKeyBindings::Quit, &q));
+}
+
+/// A broken key binding in JSON should be reported with the action
+#[test]
+fn load_keymap_from_broken_json_returns_error() {
+    let value: serde_json::Value = serde_json::from_str(r#"{ "quit": ["Contrl+q"] }"#).unwrap();
+    let Err(crossterm_keybind::Error::ParseKeyBindingError { action, .. }) =
+        TestKeyBindings::load_keymap_from(value)
+    else {
+        panic!("expected ParseKeyBindingError for a broken key binding");
+    };
+    assert_eq!(action, "quit");
+
+    let value: serde_json::Value = serde_json::from_str(r#"["q"]"#).unwrap();
+    assert!(matches!(
+        TestKeyBindings::load_keymap_from(value),
+        Err(crossterm_keybind::Error::LoadConfigError(_))
+    ));
+
+    let value: serde_json::Value = serde_json::from_str(r#"{ "quit": ["q", null] }"#).unwrap();
+    assert!(matches!(
+        TestKeyBindings::load_keymap_from(value),
+        Err(crossterm_keybind::Error::LoadConfigError(_))
+    ));
+}
+
+/// The key config example in JSON should have the defaults
+#[cfg(feature = "json_example")]
+#[test]
+fn json_example_has_defaults() {
+    let example: serde_json::Value = serde_json::from_str(&TestKeyBindings::json_example().unwrap()).unwrap();
+    assert_eq!(example["quit"], serde_json::json!(["Control+c", "q"]));
+    assert_eq!(example["normal"]["move_down"], serde_json::json!(["j"]));
+    assert_eq!(example["inherit_defaults"], serde_json::json!(true));
+}
+
+/// The key config example in YAML should have the defaults
+#[cfg(feature = "yaml_example")]
+#[test]
+fn yaml_example_has_defaults() {
+    let example = TestKeyBindings::yaml_example().unwrap();
+    assert!(example.contains("quit:\n- Control+c\n- q\n"));
+    assert!(example.contains("normal:\n  move_down:\n  - j\n"));
+}
@@ -1,8 +1,6 @@
+use crossterm_keybind::event::{KeyCode, KeyEvent, KeyModifiers};
 use crossterm_keybind::{DisplayFormat, KeyBind, KeyBindTrait};
 
-// Each test uses a distinct enum type so the per-type BINDING_INIT static doesn't conflict
-// between tests (each derived type gets its own static via the macro expansion).
-
 /// Calling init_and_load(None) should succeed and apply the attribute-defined defaults.
 #[test]
 fn init_and_load_none_applies_defaults() {
@@ -73,7 +71,7 @@ fn init_and_load_some_patches_defaults() {
     );
 }
 
-/// Calling init_and_load with an invalid keybind string should return LoadConfigError.
+/// Calling init_and_load with an invalid keybind string should return ParseKeyBindingError.
 #[test]
 fn init_and_load_invalid_keybind_returns_error() {
     #[derive(KeyBind)]
@@ -90,11 +88,39 @@ fn init_and_load_invalid_keybind_returns_error() {
 
     let result = TestKeyBindings::init_and_load(Some(table));
     assert!(
-        matches!(result, Err(crossterm_keybind::Error::LoadConfigError(_))),
-        "expected LoadConfigError for invalid keybind, got: {result:?}"
+        matches!(
+            result,
+            Err(crossterm_keybind::Error::ParseKeyBindingError { ref action, ref key_binding, .. })
+                if action == "quit" && key_binding == "NotARealKey"
+        ),
+        "expected ParseKeyBindingError for invalid keybind, got: {result:?}"
     );
 }
 
+/// The parse error of a keybind string carries the span and the suggestion.
+#[test]
+fn init_and_load_typo_keybind_returns_suggestion() {
+    #[derive(KeyBind)]
+    enum TestKeyBindings {
+        #[keybindings["q"]]
+        Quit,
+    }
+
+    let mut table = toml::Table::new();
+    table.insert(
+        "quit".to_string(),
+        toml::Value::Array(vec![toml::Value::String("Contrl+q".to_string())]),
+    );
+
+    let Err(crossterm_keybind::Error::ParseKeyBindingError { source, .. }) =
+        TestKeyBindings::init_and_load(Some(table))
+    else {
+        panic!("expected ParseKeyBindingError for a typo keybind");
+    };
+    assert_eq!(source.span(), 0..6);
+    assert_eq!(source.suggestion(), Some("Control"));
+}
+
 /// init_and_load and init_and_load_file both set the same init guard — calling one then the
 /// other on the same type should return ConfigDoubleInitError (safety feature only).
 #[test]
@@ -114,3 +140,79 @@ fn init_and_load_prevents_double_init() {
         "expected ConfigDoubleInitError on second init, got: {second:?}"
     );
 }
+
+/// Dispatch should look up the key bindings of the patch in declaration order
+#[test]
+fn init_and_load_dispatch_in_declaration_order() {
+    #[derive(KeyBind, Debug, PartialEq)]
+    enum TestKeyBindings {
+        #[keybindings["Control+c", "q"]]
+        Quit,
+        #[keybindings["Tab"]]
+        Next,
+        #[keybindings["q"]]
+        #[keybind(allow_overlap)]
+        Close,
+    }
+
+    let mut table = toml::Table::new();
+    table.insert(
+        "next".to_string(),
+        toml::Value::Array(vec![toml::Value::String("Control+c".to_string())]),
+    );
+    TestKeyBindings::init_and_load(Some(table)).expect("init_and_load should succeed");
+
+    let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+    assert_eq!(
+        TestKeyBindings::dispatch(&ctrl_c),
+        vec![TestKeyBindings::Quit, TestKeyBindings::Next]
+    );
+    let q = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE);
+    assert!(TestKeyBindings::dispatch_iter(&q).eq([TestKeyBindings::Quit, TestKeyBindings::Close]));
+    let tab = KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE);
+    assert_eq!(TestKeyBindings::dispatch_iter(&tab).next(), None);
+}
+
+/// A manual implementation should only need the methods without default bodies
+#[test]
+fn manual_impl_needs_no_keymap() {
+    struct Quit;
+
+    impl KeyBindTrait for Quit {
+        fn init_and_load<T: crossterm_keybind::serde::Serialize>(_: Option<T>) -> Result<(), crossterm_keybind::Error> {
+            Ok(())
+        }
+
+        fn init_and_load_file(_: Option<std::path::PathBuf>) -> Result<(), crossterm_keybind::Error> {
+            Ok(())
+        }
+
+        fn match_any(&self, key_event: &KeyEvent) -> bool {
+            key_event.code == KeyCode::Char('q')
+        }
+
+        fn toml_example() -> String {
+            String::new()
+        }
+
+        fn to_toml_example<P: AsRef<std::path::Path>>(_: P) -> std::io::Result<()> {
+            Ok(())
+        }
+
+        fn key_bindings_display(&self) -> String {
+            "q".to_string()
+        }
+
+        fn key_bindings_display_with_format(&self, _: &DisplayFormat) -> String {
+            self.key_bindings_display()
+        }
+
+        fn dispatch(key_event: &KeyEvent) -> Vec<Self> {
+            if Quit.match_any(key_event) { vec![Quit] } else { Vec::new() }
+        }
+    }
+
+    Quit::init_and_load::<toml::Table>(None).unwrap();
+    let q = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE);
+    assert_eq!(Quit::dispatch(&q).len(), 1);
+}
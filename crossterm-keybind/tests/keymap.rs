@@ -0,0 +1,88 @@
+use crossterm_keybind::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm_keybind::{DisplayFormat, KeyBind, KeyBindTrait};
+
+/// An owned key map should be loaded from the defaults and the patch without touching the global
+/// key bindings
+#[test]
+fn load_keymap_with_patch() {
+    #[derive(KeyBind, Debug, PartialEq)]
+    enum TestKeyBindings {
+        #[keybindings["Control+c", "q"]]
+        Quit,
+        #[keybindings["?"]]
+        Help,
+    }
+
+    let mut table = toml::Table::new();
+    table.insert(
+        "help".to_string(),
+        toml::Value::Array(vec![toml::Value::String("F1".to_string())]),
+    );
+    let keymap = TestKeyBindings::load_keymap(Some(table)).expect("load_keymap should succeed");
+    assert!(TestKeyBindings::keymap().is_none());
+
+    let q = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE);
+    let f1 = KeyEvent::new(KeyCode::F(1), KeyModifiers::NONE);
+    assert!(keymap.match_any(&TestKeyBindings::Quit, &q));
+    assert!(!keymap.match_any(&TestKeyBindings::Help, &q));
+    assert_eq!(keymap.dispatch(&f1), vec![TestKeyBindings::Help]);
+    assert!(keymap.dispatch_iter(&q).eq([TestKeyBindings::Quit]));
+    assert_eq!(
+        keymap.key_bindings_display_with_format(&TestKeyBindings::Quit, &DisplayFormat::Full),
+        "Control+c | q"
+    );
+}
+
+/// The global key map should be replaceable after initialization
+#[test]
+fn set_keymap_replaces_global_keybindings() {
+    #[derive(KeyBind, Debug, PartialEq)]
+    enum TestKeyBindings {
+        #[keybindings["q"]]
+        Quit,
+    }
+
+    TestKeyBindings::init_and_load::<toml::Table>(None).expect("init should succeed");
+    let q = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE);
+    let x = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE);
+    assert!(TestKeyBindings::Quit.match_any(&q));
+    let previous = TestKeyBindings::keymap().expect("the global key map is initialized");
+
+    let mut table = toml::Table::new();
+    table.insert(
+        "quit".to_string(),
+        toml::Value::Array(vec![toml::Value::String("x".to_string())]),
+    );
+    TestKeyBindings::set_keymap(TestKeyBindings::load_keymap(Some(table)).unwrap());
+    assert!(!TestKeyBindings::Quit.match_any(&q));
+    assert!(TestKeyBindings::Quit.match_any(&x));
+    assert_eq!(TestKeyBindings::dispatch(&x), vec![TestKeyBindings::Quit]);
+
+    // A key map in use is kept by its owner
+    assert!(previous.match_any(&TestKeyBindings::Quit, &q));
+}
+
+/// The legacy mode should be an option of each key map, folding the keys legacy terminals can not
+/// distinguish only in the key map asking for it
+#[test]
+fn legacy_mode_is_per_keymap() {
+    #[derive(KeyBind, Debug, PartialEq)]
+    enum TestKeyBindings {
+        #[keybindings["Control+i"]]
+        Indent,
+        #[keybindings["Tab"]]
+        NextField,
+    }
+
+    let keymap = TestKeyBindings::load_keymap::<toml::Table>(None).unwrap();
+    let legacy = TestKeyBindings::load_keymap::<toml::Table>(None)
+        .unwrap()
+        .with_legacy_mode(true);
+    let tab = KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE);
+    assert!(!keymap.legacy_mode());
+    assert!(legacy.legacy_mode());
+    assert_eq!(keymap.dispatch(&tab), vec![TestKeyBindings::NextField]);
+    assert!(keymap.legacy_ambiguities().is_empty());
+    assert!(legacy.match_any(&TestKeyBindings::Indent, &tab));
+    assert_eq!(legacy.legacy_ambiguities().len(), 1);
+}
@@ -0,0 +1,108 @@
+use crossterm_keybind::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm_keybind::{KeyBind, KeyBindTrait, KeymapStack};
+
+#[derive(KeyBind, Debug, PartialEq)]
+enum GlobalKeyBindings {
+    #[keybindings["Control+c"]]
+    Quit,
+    #[keybindings["Esc"]]
+    Back,
+}
+
+mod panel {
+    use crossterm_keybind::KeyBind;
+
+    #[derive(KeyBind, Debug, PartialEq)]
+    pub enum PanelKeyBindings {
+        #[keybindings["j"]]
+        Down,
+        #[keybindings(mode = "normal", ["n"])]
+        NormalDown,
+        #[keybindings["Esc"]]
+        Close,
+    }
+}
+
+mod dialog {
+    use crossterm_keybind::KeyBind;
+
+    #[derive(KeyBind, Debug, PartialEq)]
+    pub enum DialogKeyBindings {
+        #[keybindings["Enter"]]
+        Confirm,
+    }
+}
+
+use dialog::DialogKeyBindings;
+use panel::PanelKeyBindings;
+
+#[derive(Debug, PartialEq)]
+enum Action {
+    Global(GlobalKeyBindings),
+    Panel(PanelKeyBindings),
+    Dialog(DialogKeyBindings),
+}
+
+/// Keys should be dispatched to the topmost layer binding them, and opaque layers swallow the rest
+#[test]
+fn keymap_stack_falls_through_layers() {
+    GlobalKeyBindings::init_and_load::<toml::Table>(None).unwrap();
+
+    let esc = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+    let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+    let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+    let x = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE);
+
+    let mut stack = KeymapStack::new();
+    stack.push(GlobalKeyBindings::keymap().unwrap(), Action::Global);
+    assert_eq!(stack.dispatch(&esc), vec![Action::Global(GlobalKeyBindings::Back)]);
+
+    stack.push(PanelKeyBindings::load_keymap::<toml::Table>(None).unwrap(), Action::Panel);
+    assert_eq!(stack.dispatch(&esc), vec![Action::Panel(PanelKeyBindings::Close)]);
+    assert_eq!(stack.dispatch(&ctrl_c), vec![Action::Global(GlobalKeyBindings::Quit)]);
+    assert!(stack.dispatch(&x).is_empty());
+
+    stack.push_opaque(DialogKeyBindings::load_keymap::<toml::Table>(None).unwrap(), Action::Dialog);
+    assert_eq!(stack.len(), 3);
+    assert_eq!(stack.dispatch(&enter), vec![Action::Dialog(DialogKeyBindings::Confirm)]);
+    assert!(stack.dispatch(&ctrl_c).is_empty());
+
+    assert!(stack.pop());
+    assert_eq!(stack.dispatch(&ctrl_c), vec![Action::Global(GlobalKeyBindings::Quit)]);
+    stack.clear();
+    assert!(stack.is_empty());
+    assert!(!stack.pop());
+}
+
+/// The mode and the context should be passed to each layer, a layer without the mode falls back
+/// to its key bindings in all modes
+#[test]
+fn keymap_stack_dispatches_in_mode_with_context() {
+    let patch: toml::Table = toml::from_str(
+        r#"
+        close = [{ key = "Esc", when = "panelFocused" }]
+
+        [normal]
+        normal_down = ["Down"]
+        "#,
+    )
+    .unwrap();
+    let mut stack = KeymapStack::new();
+    stack.push(GlobalKeyBindings::load_keymap::<toml::Table>(None).unwrap(), Action::Global);
+    stack.push(PanelKeyBindings::load_keymap(Some(patch)).unwrap(), Action::Panel);
+
+    let esc = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+    let down = KeyEvent::new(KeyCode::Down, KeyModifiers::NONE);
+    assert_eq!(stack.dispatch(&esc), vec![Action::Global(GlobalKeyBindings::Back)]);
+    assert_eq!(
+        stack.dispatch_with(&esc, &["panelFocused"]),
+        vec![Action::Panel(PanelKeyBindings::Close)]
+    );
+    assert!(stack.dispatch(&down).is_empty());
+    assert_eq!(stack.dispatch_in("normal", &down), vec![Action::Panel(PanelKeyBindings::NormalDown)]);
+    assert_eq!(
+        stack.dispatch_in_with("normal", &esc, &["panelFocused"]),
+        vec![Action::Panel(PanelKeyBindings::Close)]
+    );
+    assert_eq!(stack.dispatch_in("normal", &esc), vec![Action::Global(GlobalKeyBindings::Back)]);
+}
@@ -0,0 +1,160 @@
+use crossterm_keybind::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm_keybind::{ConfigLayer, KeyBind, KeyBindTrait};
+
+/// The layers should be applied in order, and the layer changing each key bindings last should be
+/// recorded
+#[test]
+fn load_layers_in_order() {
+    #[derive(KeyBind, Debug, PartialEq)]
+    enum TestKeyBindings {
+        #[keybindings["Control+c", "q"]]
+        Quit,
+        #[keybindings["F1", "?"]]
+        ToggleHelp,
+        #[keybindings["Alt+<digit>"]]
+        SwitchTab(u8),
+        #[keybindings(mode = "normal", ["j"])]
+        MoveDown,
+    }
+
+    let dir = std::env::temp_dir().join(format!("crossterm-keybind-layers-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let system = dir.join("system.toml");
+    let user = dir.join("user.toml");
+    std::fs::write(&system, "quit = [\"Control+q\"]\ntoggle_help = [\"h\"]\n").unwrap();
+    std::fs::write(
+        &user,
+        "quit = { add = [\"Q\"] }\ntoggle_help = [\"h\"]\n\n[normal]\nmove_down = [\"Down\"]\n",
+    )
+    .unwrap();
+    let project: toml::Table = toml::from_str(r#"switch_tab = ["Control+<digit>"]"#).unwrap();
+
+    let keymap = TestKeyBindings::load_keymap_layers(vec![
+        ConfigLayer::file(&system),
+        ConfigLayer::file(&user),
+        ConfigLayer::file(dir.join("missing.toml")),
+        ConfigLayer::table("project", &project).unwrap(),
+    ])
+    .expect("load_keymap_layers should succeed");
+    assert_eq!(
+        format!("{:?}", keymap.key_bindings(&TestKeyBindings::Quit)),
+        r#"["Control+q", "Q"]"#
+    );
+    let ctrl_2 = KeyEvent::new(KeyCode::Char('2'), KeyModifiers::CONTROL);
+    let down = KeyEvent::new(KeyCode::Down, KeyModifiers::NONE);
+    assert_eq!(keymap.dispatch(&ctrl_2), vec![TestKeyBindings::SwitchTab(2)]);
+    assert_eq!(keymap.dispatch_in("normal", &down), vec![TestKeyBindings::MoveDown]);
+
+    assert_eq!(keymap.layer_of(&TestKeyBindings::Quit), Some(&ConfigLayer::File(user.clone())));
+    // the same key bindings are kept from the layer changing them
+    assert_eq!(
+        keymap.layer_of(&TestKeyBindings::ToggleHelp),
+        Some(&ConfigLayer::File(system.clone()))
+    );
+    assert_eq!(
+        keymap.layer_of(&TestKeyBindings::SwitchTab(2)).map(|l| l.to_string()),
+        Some("project".to_string())
+    );
+    assert_eq!(
+        keymap.layer_of_in("normal", &TestKeyBindings::MoveDown),
+        Some(&ConfigLayer::File(user.clone()))
+    );
+    assert_eq!(keymap.layer_of(&TestKeyBindings::MoveDown), None);
+    assert_eq!(keymap.layers().count(), 4);
+
+    // the defaults are not from any layer
+    let keymap = TestKeyBindings::load_keymap_layers(vec![ConfigLayer::file(dir.join("missing.toml"))])
+        .expect("load_keymap_layers should succeed");
+    assert_eq!(keymap.layer_of(&TestKeyBindings::Quit), None);
+    assert_eq!(keymap.layer_of(&TestKeyBindings::SwitchTab(2)), None);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Starting from empty in a layer should only unbind the defaults, not the key bindings of the
+/// layers below
+#[test]
+fn start_from_empty_over_lower_layers() {
+    #[derive(KeyBind, Debug, PartialEq)]
+    enum TestKeyBindings {
+        #[keybindings["Control+c", "q"]]
+        Quit,
+        #[keybindings["F1", "?"]]
+        ToggleHelp,
+        #[keybindings["Alt+<digit>"]]
+        SwitchTab(u8),
+        #[keybindings(mode = "normal", ["j"])]
+        MoveDown,
+    }
+
+    let system: toml::Table = toml::from_str(
+        r#"
+        quit = ["Control+q"]
+
+        [normal]
+        move_down = ["Down"]
+        "#,
+    )
+    .unwrap();
+    let user: toml::Table = toml::from_str(
+        r#"
+        inherit_defaults = false
+        quit = { add = ["Q"] }
+        switch_tab = { add = ["Control+<digit>"] }
+        "#,
+    )
+    .unwrap();
+    let keymap = TestKeyBindings::load_keymap_layers(vec![
+        ConfigLayer::table("system", &system).unwrap(),
+        ConfigLayer::table("user", &user).unwrap(),
+    ])
+    .expect("load_keymap_layers should succeed");
+    assert_eq!(
+        format!("{:?}", keymap.key_bindings(&TestKeyBindings::Quit)),
+        r#"["Control+q", "Q"]"#
+    );
+    assert!(keymap.key_bindings(&TestKeyBindings::ToggleHelp).iter().next().is_none());
+    let alt_2 = KeyEvent::new(KeyCode::Char('2'), KeyModifiers::ALT);
+    let ctrl_2 = KeyEvent::new(KeyCode::Char('2'), KeyModifiers::CONTROL);
+    let down = KeyEvent::new(KeyCode::Down, KeyModifiers::NONE);
+    assert!(keymap.dispatch(&alt_2).is_empty());
+    assert_eq!(keymap.dispatch(&ctrl_2), vec![TestKeyBindings::SwitchTab(2)]);
+    assert_eq!(keymap.dispatch_in("normal", &down), vec![TestKeyBindings::MoveDown]);
+}
+
+/// A broken layer should be reported
+#[test]
+fn load_broken_layer_returns_error() {
+    #[derive(KeyBind, Debug, PartialEq)]
+    enum TestKeyBindings {
+        #[keybindings["q"]]
+        Quit,
+    }
+
+    let broken: toml::Table = toml::from_str(r#"quit = ["Contrl+q"]"#).unwrap();
+    let Err(crossterm_keybind::Error::ParseKeyBindingError { action, .. }) =
+        TestKeyBindings::load_keymap_layers(vec![ConfigLayer::table("broken", &broken).unwrap()])
+    else {
+        panic!("expected ParseKeyBindingError for a broken layer");
+    };
+    assert_eq!(action, "quit");
+}
+
+/// A layer which is not valid TOML should be reported rather than skipped by the list patches
+#[test]
+fn load_invalid_toml_layer_returns_error() {
+    #[derive(KeyBind, Debug, PartialEq)]
+    enum TestKeyBindings {
+        #[keybindings["q"]]
+        Quit,
+    }
+
+    let layer = ConfigLayer::Toml {
+        name: "broken".to_string(),
+        contents: "quit = { add = [\"Q\"]\n".to_string(),
+    };
+    assert!(matches!(
+        TestKeyBindings::load_keymap_layers(vec![layer]),
+        Err(crossterm_keybind::Error::LoadConfigError(_))
+    ));
+}
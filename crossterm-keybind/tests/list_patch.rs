@@ -0,0 +1,73 @@
+use crossterm_keybind::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm_keybind::{KeyBind, KeyBindTrait};
+
+/// The key bindings patched with `add` and `remove` should be computed against the defaults
+#[test]
+fn add_and_remove_key_bindings() {
+    #[derive(KeyBind, Debug, PartialEq)]
+    enum TestKeyBindings {
+        #[keybindings["Control+c", "Q", "q"]]
+        Quit,
+        #[keybindings["Alt+<digit>"]]
+        SwitchTab(u8),
+        #[keybindings(mode = "normal", ["j"])]
+        MoveDown,
+    }
+
+    let patch: toml::Table = toml::from_str(
+        r#"
+        quit = { add = ["Ctrl+q", "q"], remove = ["Q"] }
+        switch_tab = { add = ["Control+<digit>"] }
+
+        [normal]
+        move_down = { add = [{ key = "Down", when = "!editing" }] }
+        "#,
+    )
+    .unwrap();
+    let keymap = TestKeyBindings::load_keymap(Some(patch)).expect("load_keymap should succeed");
+    assert_eq!(
+        format!("{:?}", keymap.key_bindings(&TestKeyBindings::Quit)),
+        r#"["Control+c", "q", "Control+q"]"#
+    );
+    let ctrl_q = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::CONTROL);
+    let alt_2 = KeyEvent::new(KeyCode::Char('2'), KeyModifiers::ALT);
+    let ctrl_2 = KeyEvent::new(KeyCode::Char('2'), KeyModifiers::CONTROL);
+    let j = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE);
+    let down = KeyEvent::new(KeyCode::Down, KeyModifiers::NONE);
+    assert_eq!(keymap.dispatch(&ctrl_q), vec![TestKeyBindings::Quit]);
+    assert_eq!(keymap.dispatch(&alt_2), vec![TestKeyBindings::SwitchTab(2)]);
+    assert_eq!(keymap.dispatch(&ctrl_2), vec![TestKeyBindings::SwitchTab(2)]);
+    assert_eq!(keymap.dispatch_in("normal", &j), vec![TestKeyBindings::MoveDown]);
+    assert_eq!(keymap.dispatch_in("normal", &down), vec![TestKeyBindings::MoveDown]);
+    assert!(keymap.conflicts().is_empty());
+
+    let patch: toml::Table = toml::from_str(
+        r#"
+        quit = { add = ["Control+q", "Q"] }
+        switch_tab = { add = ["Alt+<digit>"] }
+        "#,
+    )
+    .unwrap();
+    let keymap = TestKeyBindings::load_keymap(Some(patch)).expect("load_keymap should succeed");
+    assert_eq!(
+        format!("{:?}", keymap.key_bindings(&TestKeyBindings::Quit)),
+        r#"["Control+c", "Q", "q", "Control+q"]"#
+    );
+    assert_eq!(keymap.dispatch(&alt_2), vec![TestKeyBindings::SwitchTab(2)]);
+}
+
+/// A patch with an unknown field should be reported
+#[test]
+fn unknown_field_of_list_patch_returns_error() {
+    #[derive(KeyBind, Debug)]
+    enum TestKeyBindings {
+        #[keybindings["q"]]
+        Quit,
+    }
+
+    let patch: toml::Table = toml::from_str(r#"quit = { ad = ["x"] }"#).unwrap();
+    let Err(crossterm_keybind::Error::LoadConfigError(e)) = TestKeyBindings::load_keymap(Some(patch)) else {
+        panic!("expected LoadConfigError for an unknown field");
+    };
+    assert!(e.contains("unknown field `ad` of `quit`"));
+}
@@ -0,0 +1,75 @@
+use crossterm_keybind::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm_keybind::{KeyBind, KeyBindTrait};
+
+/// Key bindings in modes should be in the tables of the modes, and be dispatched with the active
+/// mode together with the key bindings in all modes
+#[test]
+fn dispatch_in_modes() {
+    #[derive(KeyBind, Debug, PartialEq)]
+    enum TestKeyBindings {
+        #[keybindings["Control+c"]]
+        Quit,
+        /// Move the cursor down
+        #[keybindings(mode = "normal", ["j"])]
+        #[keybindings(mode = "insert", ["Down"])]
+        MoveDown,
+        #[keybindings(mode = "insert", ["j"])]
+        InsertJ,
+    }
+
+    let example = TestKeyBindings::toml_example();
+    let table: toml::Table = toml::from_str(&example).expect("example should be valid toml");
+    assert!(table.contains_key("quit"));
+    assert!(!table.contains_key("move_down"));
+    assert_eq!(table["normal"]["move_down"][0].as_str(), Some("j"));
+    assert_eq!(table["insert"]["insert_j"][0].as_str(), Some("j"));
+
+    let patch: toml::Table = toml::from_str(
+        r#"
+        [insert]
+        move_down = ["Control+n"]
+        "#,
+    )
+    .unwrap();
+    TestKeyBindings::init_and_load(Some(patch)).expect("init_and_load should succeed");
+
+    let j = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE);
+    let ctrl_n = KeyEvent::new(KeyCode::Char('n'), KeyModifiers::CONTROL);
+    let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+    assert!(TestKeyBindings::dispatch(&j).is_empty());
+    assert_eq!(TestKeyBindings::dispatch_in("normal", &j), vec![TestKeyBindings::MoveDown]);
+    assert_eq!(TestKeyBindings::dispatch_in("insert", &j), vec![TestKeyBindings::InsertJ]);
+    assert_eq!(TestKeyBindings::dispatch_in("insert", &ctrl_n), vec![TestKeyBindings::MoveDown]);
+    assert_eq!(TestKeyBindings::dispatch_in("insert", &ctrl_c), vec![TestKeyBindings::Quit]);
+    assert!(TestKeyBindings::MoveDown.match_any_in("normal", &j));
+    assert!(!TestKeyBindings::MoveDown.match_any_in("search", &j));
+    assert!(TestKeyBindings::Quit.match_any_in("search", &ctrl_c));
+
+    let keymap = TestKeyBindings::keymap().unwrap();
+    assert_eq!(keymap.modes().collect::<Vec<_>>(), vec!["normal", "insert"]);
+    assert!(keymap.key_bindings_in("normal", &TestKeyBindings::InsertJ).unwrap().iter().next().is_none());
+}
+
+/// A broken key binding in the table of a mode should be located with the mode
+#[test]
+fn init_and_load_invalid_keybind_in_mode_returns_error() {
+    #[derive(KeyBind, Debug)]
+    enum TestKeyBindings {
+        #[keybindings(mode = "normal", ["j"])]
+        MoveDown,
+    }
+
+    let patch: toml::Table = toml::from_str(
+        r#"
+        [normal]
+        move_down = ["Contrl+n"]
+        "#,
+    )
+    .unwrap();
+    let Err(crossterm_keybind::Error::ParseKeyBindingError { action, .. }) =
+        TestKeyBindings::init_and_load(Some(patch))
+    else {
+        panic!("expected ParseKeyBindingError for a typo keybind");
+    };
+    assert_eq!(action, "normal.move_down");
+}
@@ -0,0 +1,105 @@
+use crossterm_keybind::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm_keybind::{KeyBind, KeyBindTrait};
+use std::time::{Duration, SystemTime};
+
+fn write_config(path: &std::path::Path, contents: &str, modified: SystemTime) {
+    std::fs::write(path, contents).unwrap();
+    std::fs::File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+}
+
+/// The global key map should follow the changes of the config file, and keep the previous one on
+/// a broken edit
+#[test]
+fn reload_if_changed_applies_file_changes() {
+    #[derive(KeyBind, Debug, PartialEq)]
+    enum TestKeyBindings {
+        #[keybindings["q"]]
+        Quit,
+    }
+
+    let path = std::env::temp_dir().join(format!("crossterm-keybind-reload-{}.toml", std::process::id()));
+    let start = SystemTime::now();
+    write_config(&path, r#"quit = ["x"]"#, start);
+    TestKeyBindings::init_and_load_file(Some(path.clone())).expect("init should succeed");
+
+    let x = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE);
+    let y = KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE);
+    assert!(TestKeyBindings::Quit.match_any(&x));
+    assert!(!TestKeyBindings::reload_if_changed().unwrap());
+
+    write_config(&path, r#"quit = ["y"]"#, start + Duration::from_secs(1));
+    assert!(TestKeyBindings::reload_if_changed().unwrap());
+    assert!(TestKeyBindings::Quit.match_any(&y));
+    assert!(!TestKeyBindings::Quit.match_any(&x));
+
+    write_config(&path, r#"quit = ["Contrl+y"]"#, start + Duration::from_secs(2));
+    let Err(crossterm_keybind::Error::ParseKeyBindingError { key_binding, .. }) =
+        TestKeyBindings::reload_if_changed()
+    else {
+        panic!("expected ParseKeyBindingError for a broken edit");
+    };
+    assert_eq!(key_binding, "Contrl+y");
+    assert!(TestKeyBindings::Quit.match_any(&y));
+
+    write_config(&path, r#"quit = ["x"]"#, start + Duration::from_secs(3));
+    TestKeyBindings::reload().expect("reload should succeed");
+    assert!(TestKeyBindings::Quit.match_any(&x));
+    assert!(!TestKeyBindings::reload_if_changed().unwrap());
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+/// Reload before initialization should be an error
+#[test]
+fn reload_before_init_returns_error() {
+    #[derive(KeyBind)]
+    enum TestKeyBindings {
+        #[keybindings["q"]]
+        Quit,
+    }
+
+    assert!(matches!(
+        TestKeyBindings::reload(),
+        Err(crossterm_keybind::Error::ConfigNotInitError)
+    ));
+}
+
+/// The global key map loaded from layers should follow the config files of the layers, including
+/// a file created after loading
+#[test]
+fn reload_if_changed_applies_layer_changes() {
+    #[derive(KeyBind, Debug, PartialEq)]
+    enum TestKeyBindings {
+        #[keybindings["q"]]
+        Quit,
+    }
+
+    let dir = std::env::temp_dir();
+    let system = dir.join(format!("crossterm-keybind-reload-system-{}.toml", std::process::id()));
+    let project = dir.join(format!("crossterm-keybind-reload-project-{}.toml", std::process::id()));
+    let start = SystemTime::now();
+    write_config(&system, r#"quit = ["x"]"#, start);
+    TestKeyBindings::init_and_load_layers(vec![
+        crossterm_keybind::ConfigLayer::file(&system),
+        crossterm_keybind::ConfigLayer::file(&project),
+    ])
+    .expect("init should succeed");
+
+    let x = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE);
+    let y = KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE);
+    assert!(TestKeyBindings::Quit.match_any(&x));
+    assert!(!TestKeyBindings::reload_if_changed().unwrap());
+
+    write_config(&project, r#"quit = ["y"]"#, start + Duration::from_secs(1));
+    assert!(TestKeyBindings::reload_if_changed().unwrap());
+    assert!(TestKeyBindings::Quit.match_any(&y));
+    assert!(!TestKeyBindings::Quit.match_any(&x));
+
+    std::fs::remove_file(&system).unwrap();
+    std::fs::remove_file(&project).unwrap();
+}
@@ -0,0 +1,80 @@
+use crossterm_keybind::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm_keybind::{DisplayFormat, KeyBind, KeyBindTrait, SequenceMatch};
+use std::time::{Duration, Instant};
+
+/// Key sequences from the attributes and the patch should be matched by the sequence matcher
+#[test]
+fn sequence_matcher_matches_key_sequences() {
+    #[derive(KeyBind, Clone, Debug, PartialEq)]
+    enum TestKeyBindings {
+        #[keybindings["g g"]]
+        Top,
+        #[keybindings["Control+x Control+s"]]
+        Save,
+    }
+
+    let mut table = toml::Table::new();
+    table.insert(
+        "save".to_string(),
+        toml::Value::Array(vec![toml::Value::String("Ctrl+x s".to_string())]),
+    );
+    TestKeyBindings::init_and_load(Some(table)).expect("init_and_load should succeed");
+
+    assert!(TestKeyBindings::toml_example().contains(r#"top = ["g g"]"#));
+    assert_eq!(
+        TestKeyBindings::Save.key_bindings_display_with_format(&DisplayFormat::Full),
+        "Control+x s"
+    );
+
+    let g = KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE);
+    assert!(
+        TestKeyBindings::dispatch(&g).is_empty(),
+        "dispatch only matches single key strokes"
+    );
+
+    let mut matcher = TestKeyBindings::sequence_matcher();
+    assert_eq!(matcher.feed(&g), SequenceMatch::Pending);
+    assert_eq!(
+        matcher.feed(&g),
+        SequenceMatch::Matched(vec![TestKeyBindings::Top])
+    );
+
+    let ctrl_x = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::CONTROL);
+    let s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE);
+    assert_eq!(matcher.feed(&ctrl_x), SequenceMatch::Pending);
+    assert_eq!(
+        matcher.feed(&s),
+        SequenceMatch::Matched(vec![TestKeyBindings::Save])
+    );
+}
+
+/// The sequence timeout from the attribute can be overridden by the patch
+#[test]
+fn sequence_timeout_from_attribute_and_patch() {
+    #[derive(KeyBind, Clone, Debug, PartialEq)]
+    #[keybind(sequence_timeout_ms = 300)]
+    enum TestKeyBindings {
+        #[keybindings["g"]]
+        Go,
+        #[keybindings["g g"]]
+        Top,
+    }
+
+    assert!(TestKeyBindings::toml_example().contains("sequence_timeout_ms = 300"));
+
+    let mut table = toml::Table::new();
+    table.insert("sequence_timeout_ms".to_string(), toml::Value::Integer(800));
+    TestKeyBindings::init_and_load(Some(table)).expect("init_and_load should succeed");
+
+    let mut matcher = TestKeyBindings::sequence_matcher();
+    assert_eq!(matcher.timeout(), Some(Duration::from_millis(800)));
+
+    let start = Instant::now();
+    let g = KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE);
+    assert_eq!(matcher.feed_at(&g, start), SequenceMatch::Pending);
+    assert_eq!(matcher.poll_timeout(start + Duration::from_millis(300)), None);
+    assert_eq!(
+        matcher.poll_timeout(start + Duration::from_millis(800)),
+        Some(SequenceMatch::Matched(vec![TestKeyBindings::Go]))
+    );
+}
@@ -0,0 +1,107 @@
+use crossterm_keybind::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm_keybind::{KeyBind, KeyBindTrait, Origin};
+
+/// A key binding set to an empty list should be unbound, and the others keep the defaults
+#[test]
+fn unbind_with_empty_list() {
+    #[derive(KeyBind, Debug, PartialEq)]
+    enum TestKeyBindings {
+        #[keybindings["Control+c", "q"]]
+        Quit,
+        #[keybindings["F1"]]
+        ToggleHelp,
+        #[keybindings(mode = "normal", ["j"])]
+        MoveDown,
+    }
+
+    let patch: toml::Table = toml::from_str(
+        r#"
+        quit = []
+
+        [normal]
+        move_down = []
+        "#,
+    )
+    .unwrap();
+    let keymap = TestKeyBindings::load_keymap(Some(patch)).expect("load_keymap should succeed");
+    let q = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE);
+    let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+    let f1 = KeyEvent::new(KeyCode::F(1), KeyModifiers::NONE);
+    let j = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE);
+    assert!(keymap.dispatch(&q).is_empty());
+    assert!(keymap.dispatch(&ctrl_c).is_empty());
+    assert!(!keymap.match_any(&TestKeyBindings::Quit, &q));
+    assert!(keymap.dispatch_in("normal", &j).is_empty());
+    assert_eq!(keymap.dispatch(&f1), vec![TestKeyBindings::ToggleHelp]);
+}
+
+/// Without inheriting the defaults, only the key bindings in the config should be bound
+#[test]
+fn start_from_empty() {
+    #[derive(KeyBind, Debug, PartialEq)]
+    enum TestKeyBindings {
+        #[keybindings["Control+c", "q"]]
+        Quit,
+        #[keybindings["F1", "?"]]
+        ToggleHelp,
+        #[keybindings["Alt+<digit>"]]
+        SwitchTab(u8),
+        #[keybindings(mode = "normal", ["j"])]
+        MoveDown,
+    }
+
+    let patch: toml::Table = toml::from_str(
+        r#"
+        inherit_defaults = false
+        quit = ["Control+q"]
+        toggle_help = { add = ["F1"], remove = ["?"] }
+        "#,
+    )
+    .unwrap();
+    let keymap = TestKeyBindings::load_keymap(Some(patch)).expect("load_keymap should succeed");
+    assert_eq!(
+        format!("{:?}", keymap.key_bindings(&TestKeyBindings::Quit)),
+        r#"["Control+q"]"#
+    );
+    assert_eq!(
+        format!("{:?}", keymap.key_bindings(&TestKeyBindings::ToggleHelp)),
+        r#"["F1"]"#
+    );
+    let q = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE);
+    let ctrl_q = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::CONTROL);
+    let question = KeyEvent::new(KeyCode::Char('?'), KeyModifiers::NONE);
+    let alt_2 = KeyEvent::new(KeyCode::Char('2'), KeyModifiers::ALT);
+    let j = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE);
+    assert!(keymap.dispatch(&q).is_empty());
+    assert_eq!(keymap.dispatch(&ctrl_q), vec![TestKeyBindings::Quit]);
+    assert!(keymap.dispatch(&question).is_empty());
+    assert!(keymap.dispatch(&alt_2).is_empty());
+    assert!(keymap.dispatch_in("normal", &j).is_empty());
+
+    let patch: toml::Table = toml::from_str(
+        r#"
+        inherit_defaults = false
+        quit = ["q"]
+        toggle_help = ["q"]
+        "#,
+    )
+    .unwrap();
+    let keymap = TestKeyBindings::load_keymap(Some(patch)).expect("load_keymap should succeed");
+    let conflicts = keymap.conflicts();
+    assert_eq!(conflicts.len(), 1);
+    assert!(conflicts[0].claims.iter().all(|c| c.origin == Origin::User));
+}
+
+/// The default config should document both ways to unbind the keys
+#[test]
+fn toml_example_documents_unbinding() {
+    #[derive(KeyBind, Debug, PartialEq)]
+    enum TestKeyBindings {
+        #[keybindings["q"]]
+        Quit,
+    }
+
+    let example = TestKeyBindings::toml_example();
+    assert!(example.contains("inherit_defaults = true"));
+    assert!(example.contains("quit = []"));
+}
@@ -0,0 +1,111 @@
+use crossterm_keybind::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm_keybind::{KeyBind, KeyBindTrait};
+
+/// Variants with data should be bound to values, each value has its own key bindings in the config
+#[test]
+fn dispatch_values_of_variants() {
+    #[derive(KeyBind, Debug, PartialEq)]
+    enum TestKeyBindings {
+        #[keybindings["Control+c"]]
+        Quit,
+        /// Switch to the tab
+        #[keybindings("Alt+1" => SwitchTab(1), "Alt+2" => SwitchTab(2), "F2" => SwitchTab(2))]
+        SwitchTab(u8),
+        #[keybindings(mode = "normal", "h" => Move('h'), "l" => Move('l'))]
+        Move(char),
+        Unbound(u8),
+    }
+
+    let example = TestKeyBindings::toml_example();
+    let table: toml::Table = toml::from_str(&example).expect("example should be valid toml");
+    assert_eq!(table["switch_tab_1"][0].as_str(), Some("Alt+1"));
+    assert_eq!(table["switch_tab_2"][1].as_str(), Some("F2"));
+    assert_eq!(table["normal"]["move_l"][0].as_str(), Some("l"));
+    assert_eq!(example.matches("Switch to the tab").count(), 1);
+
+    let patch: toml::Table = toml::from_str(
+        r#"
+        switch_tab_2 = ["Control+2"]
+        "#,
+    )
+    .unwrap();
+    TestKeyBindings::init_and_load(Some(patch)).expect("init_and_load should succeed");
+
+    let alt_1 = KeyEvent::new(KeyCode::Char('1'), KeyModifiers::ALT);
+    let alt_2 = KeyEvent::new(KeyCode::Char('2'), KeyModifiers::ALT);
+    let ctrl_2 = KeyEvent::new(KeyCode::Char('2'), KeyModifiers::CONTROL);
+    let l = KeyEvent::new(KeyCode::Char('l'), KeyModifiers::NONE);
+    assert_eq!(TestKeyBindings::dispatch(&alt_1), vec![TestKeyBindings::SwitchTab(1)]);
+    assert!(TestKeyBindings::dispatch(&alt_2).is_empty());
+    assert_eq!(TestKeyBindings::dispatch(&ctrl_2), vec![TestKeyBindings::SwitchTab(2)]);
+    assert_eq!(TestKeyBindings::dispatch_in("normal", &l), vec![TestKeyBindings::Move('l')]);
+    assert!(TestKeyBindings::SwitchTab(1).match_any(&alt_1));
+    assert!(!TestKeyBindings::SwitchTab(3).match_any(&alt_1));
+    assert!(!TestKeyBindings::Unbound(1).match_any(&alt_1));
+    assert_eq!(TestKeyBindings::SwitchTab(3).key_bindings_display(), "");
+}
+
+/// A variant with a single field should be bound with the `<digit>` placeholder, which passes the
+/// digit into the variant
+#[test]
+fn dispatch_digit_of_variants() {
+    #[derive(KeyBind, Debug, Clone, PartialEq)]
+    enum TestKeyBindings {
+        /// Switch to the tab
+        #[keybindings["Alt+<digit>"]]
+        SwitchTab(usize),
+        #[keybindings["Control+c"]]
+        Quit,
+    }
+
+    let example = TestKeyBindings::toml_example();
+    assert!(example.contains(r#"switch_tab = ["Alt+<digit>"]"#));
+
+    let patch: toml::Table = toml::from_str(
+        r#"
+        switch_tab = ["Control+<digit>", "g <digit>"]
+        "#,
+    )
+    .unwrap();
+    TestKeyBindings::init_and_load(Some(patch)).expect("init_and_load should succeed");
+
+    let alt_3 = KeyEvent::new(KeyCode::Char('3'), KeyModifiers::ALT);
+    let ctrl_3 = KeyEvent::new(KeyCode::Char('3'), KeyModifiers::CONTROL);
+    let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+    assert!(TestKeyBindings::dispatch(&alt_3).is_empty());
+    assert_eq!(TestKeyBindings::dispatch(&ctrl_3), vec![TestKeyBindings::SwitchTab(3)]);
+    assert_eq!(TestKeyBindings::dispatch(&ctrl_c), vec![TestKeyBindings::Quit]);
+    assert!(TestKeyBindings::SwitchTab(3).match_any(&ctrl_3));
+    assert!(!TestKeyBindings::SwitchTab(12).match_any(&ctrl_3));
+
+    let mut matcher = TestKeyBindings::sequence_matcher();
+    let g = KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE);
+    let seven = KeyEvent::new(KeyCode::Char('7'), KeyModifiers::NONE);
+    matcher.feed(&g);
+    assert_eq!(
+        matcher.feed(&seven),
+        crossterm_keybind::SequenceMatch::Matched(vec![TestKeyBindings::SwitchTab(7)])
+    );
+}
+
+/// A key binding without the placeholder of a variant with a digit should be reported
+#[test]
+fn init_and_load_missing_placeholder_returns_error() {
+    #[derive(KeyBind, Debug)]
+    enum TestKeyBindings {
+        #[keybindings["Alt+<digit>"]]
+        SwitchTab(u8),
+    }
+
+    let patch: toml::Table = toml::from_str(
+        r#"
+        switch_tab = ["Alt+1"]
+        "#,
+    )
+    .unwrap();
+    let Err(crossterm_keybind::Error::LoadConfigError(e)) = TestKeyBindings::init_and_load(Some(patch))
+    else {
+        panic!("expected LoadConfigError for a missing placeholder");
+    };
+    assert!(e.contains("missing placeholder"));
+}
@@ -0,0 +1,73 @@
+use crossterm_keybind::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm_keybind::{KeyBind, KeyBindTrait};
+
+/// Key bindings with `when` conditions should only be dispatched when the conditions hold in the
+/// context, and never in the empty context
+#[test]
+fn dispatch_with_conditions() {
+    #[derive(KeyBind, Debug, PartialEq)]
+    enum TestKeyBindings {
+        #[keybindings["Esc"]]
+        Cancel,
+        #[keybindings["Control+s"]]
+        Submit,
+        #[keybindings(mode = "normal", ["d"])]
+        Delete,
+    }
+
+    let patch: toml::Table = toml::from_str(
+        r#"
+        submit = ["Control+s", { key = "Enter", when = "listFocused && !readOnly" }]
+
+        [normal]
+        delete = [{ key = "d", when = "!readOnly" }]
+        "#,
+    )
+    .unwrap();
+    TestKeyBindings::init_and_load(Some(patch)).expect("init_and_load should succeed");
+
+    let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+    let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+    let d = KeyEvent::new(KeyCode::Char('d'), KeyModifiers::NONE);
+    assert!(TestKeyBindings::dispatch(&enter).is_empty());
+    assert_eq!(TestKeyBindings::dispatch(&ctrl_s), vec![TestKeyBindings::Submit]);
+    assert_eq!(
+        TestKeyBindings::dispatch_with(&enter, &["listFocused"]),
+        vec![TestKeyBindings::Submit]
+    );
+    assert!(TestKeyBindings::dispatch_with(&enter, &["listFocused", "readOnly"]).is_empty());
+    assert!(TestKeyBindings::Submit.match_any_with(&enter, &["listFocused"]));
+    assert!(!TestKeyBindings::Submit.match_any(&enter));
+
+    assert_eq!(TestKeyBindings::dispatch_in("normal", &d), vec![TestKeyBindings::Delete]);
+    assert!(TestKeyBindings::dispatch_in_with("normal", &d, &["readOnly"]).is_empty());
+    assert_eq!(
+        TestKeyBindings::Submit.key_bindings_display_with_format(&crossterm_keybind::DisplayFormat::Full),
+        "Control+s | Enter"
+    );
+}
+
+/// A broken condition should be reported with the action
+#[test]
+fn init_and_load_invalid_condition_returns_error() {
+    #[derive(KeyBind, Debug)]
+    enum TestKeyBindings {
+        #[keybindings["Enter"]]
+        Submit,
+    }
+
+    let patch: toml::Table = toml::from_str(
+        r#"
+        submit = [{ key = "Enter", when = "listFocused &&" }]
+        "#,
+    )
+    .unwrap();
+    let Err(crossterm_keybind::Error::ParseKeyBindingError {
+        action, key_binding, ..
+    }) = TestKeyBindings::init_and_load(Some(patch))
+    else {
+        panic!("expected ParseKeyBindingError for a broken condition");
+    };
+    assert_eq!(action, "submit");
+    assert_eq!(key_binding, "listFocused &&");
+}